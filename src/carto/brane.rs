@@ -145,6 +145,7 @@ impl Brane<u8> {
     pub fn save_raw_low(&self, variable: String) {
         let path_name = format!("static/{}-u8-{}.tiff", variable, self.resolution.release());
         trace!("saving brane to {}", path_name);
+        fs::create_dir_all("static").unwrap();
         TiffEncoder::new(&mut fs::File::create(&Path::new(&path_name)).unwrap())
             .unwrap()
             .write_image::<colortype::Gray8>(
@@ -224,6 +225,7 @@ impl Brane<u16> {
     pub fn save_raw(&self, variable: String) {
         let path_name = format!("static/{}-u16-{}.tiff", variable, self.resolution.release());
         trace!("saving brane to {}", path_name);
+        fs::create_dir_all("static").unwrap();
         TiffEncoder::new(&mut fs::File::create(&Path::new(&path_name)).unwrap())
             .unwrap()
             .write_image::<colortype::Gray16>(
@@ -292,10 +294,10 @@ impl<T> Brane<T>
 where
     T: Clone + Copy + PartialOrd,
 {
-    fn quantile(&self, q: f64) -> T {
+    pub fn quantile(&self, q: f64) -> T {
         let mut v = self.grid.clone();
         v.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        v[(v.len() as f64 * q) as usize]
+        v[((v.len() as f64 * q) as usize).min(v.len() - 1)]
     }
 
     fn minimum(&self) -> T {
@@ -1,4 +1,9 @@
-use crate::climate::vegetation::Vege;
+use crate::climate::{
+    holdridge::{Belt, LifeZone},
+    koppen::Koppen,
+    vegetation::Vege,
+    whittaker::Biome,
+};
 
 /* # colour spaces */
 
@@ -210,11 +215,42 @@ impl Ink<Precipitation> for MoonMeterInk {
     }
 }
 
+/* ### volcanism */
+
+/// paints only volcanic data, to be laid over another map
+pub struct VolcanicInk;
+
+impl Ink<bool> for VolcanicInk {
+    fn paint(&self, sample: bool) -> String {
+        if sample {
+            RGB::new(196, 52, 36).paint()
+        } else {
+            "none".to_string()
+        }
+    }
+}
+
+/* ### glaciers */
+
+/// paints only ice-covered data, to be laid over another map
+pub struct GlacierInk;
+
+impl Ink<Elevation> for GlacierInk {
+    fn paint(&self, sample: Elevation) -> String {
+        match sample.meters() {
+            x if x <= 0 => "none".to_string(),
+            x if x < 256 => RGB::new(232, 240, 244).paint(),
+            x if x < 1024 => RGB::new(204, 224, 236).paint(),
+            _ => RGB::new(176, 204, 224).paint(),
+        }
+    }
+}
+
 /* ### zones */
 
-pub struct KoppenInk;
+pub struct VegeInk;
 
-impl Ink<Option<Vege>> for KoppenInk {
+impl Ink<Option<Vege>> for VegeInk {
     fn paint(&self, sample: Option<Vege>) -> String {
         match sample {
             None => RGB::new(36, 36, 36).paint(),
@@ -236,6 +272,97 @@ impl Ink<Option<Vege>> for KoppenInk {
     }
 }
 
+/// the customary palette of köppen-geiger maps
+pub struct KoppenInk;
+
+impl Ink<Option<Koppen>> for KoppenInk {
+    fn paint(&self, sample: Option<Koppen>) -> String {
+        match sample {
+            None => RGB::new(36, 36, 36).paint(),
+            Some(koppen) => match koppen {
+                Koppen::Af => RGB::new(0, 0, 255).paint(),
+                Koppen::Am => RGB::new(0, 120, 255).paint(),
+                Koppen::Aw => RGB::new(70, 170, 250).paint(),
+                Koppen::As => RGB::new(120, 190, 250).paint(),
+                Koppen::BWh => RGB::new(255, 0, 0).paint(),
+                Koppen::BWk => RGB::new(255, 150, 150).paint(),
+                Koppen::BSh => RGB::new(245, 165, 0).paint(),
+                Koppen::BSk => RGB::new(255, 220, 100).paint(),
+                Koppen::Csa => RGB::new(255, 255, 0).paint(),
+                Koppen::Csb => RGB::new(200, 200, 0).paint(),
+                Koppen::Csc => RGB::new(150, 150, 0).paint(),
+                Koppen::Cwa => RGB::new(150, 255, 150).paint(),
+                Koppen::Cwb => RGB::new(100, 200, 100).paint(),
+                Koppen::Cwc => RGB::new(50, 150, 50).paint(),
+                Koppen::Cfa => RGB::new(200, 255, 80).paint(),
+                Koppen::Cfb => RGB::new(100, 255, 80).paint(),
+                Koppen::Cfc => RGB::new(50, 200, 0).paint(),
+                Koppen::Dsa => RGB::new(255, 0, 255).paint(),
+                Koppen::Dsb => RGB::new(200, 0, 200).paint(),
+                Koppen::Dsc => RGB::new(150, 50, 150).paint(),
+                Koppen::Dsd => RGB::new(150, 100, 150).paint(),
+                Koppen::Dwa => RGB::new(170, 175, 255).paint(),
+                Koppen::Dwb => RGB::new(90, 120, 220).paint(),
+                Koppen::Dwc => RGB::new(75, 80, 180).paint(),
+                Koppen::Dwd => RGB::new(50, 0, 135).paint(),
+                Koppen::Dfa => RGB::new(0, 255, 255).paint(),
+                Koppen::Dfb => RGB::new(55, 200, 255).paint(),
+                Koppen::Dfc => RGB::new(0, 125, 125).paint(),
+                Koppen::Dfd => RGB::new(0, 70, 95).paint(),
+                Koppen::ET => RGB::new(178, 178, 178).paint(),
+                Koppen::EF => RGB::new(102, 102, 102).paint(),
+            },
+        }
+    }
+}
+
+/// hue follows the humidity province and brightness the belt
+pub struct HoldridgeInk;
+
+impl Ink<Option<LifeZone>> for HoldridgeInk {
+    fn paint(&self, sample: Option<LifeZone>) -> String {
+        match sample {
+            None => RGB::new(36, 36, 36).paint(),
+            Some(LifeZone {
+                belt: Belt::Polar, ..
+            }) => RGB::new(240, 244, 248).paint(),
+            Some(zone) => {
+                let brt = match zone.belt {
+                    Belt::Subpolar => 0.96,
+                    Belt::Boreal => 0.88,
+                    Belt::CoolTemperate => 0.80,
+                    Belt::WarmTemperate => 0.72,
+                    Belt::Subtropical => 0.64,
+                    _ => 0.56,
+                };
+                HSB::new(0.60 - 0.08 * zone.humidity as f64, 0.72, brt).paint()
+            }
+        }
+    }
+}
+
+/// the colours of the usual drawing of whittaker's diagram
+pub struct WhittakerInk;
+
+impl Ink<Option<Biome>> for WhittakerInk {
+    fn paint(&self, sample: Option<Biome>) -> String {
+        match sample {
+            None => RGB::new(36, 36, 36).paint(),
+            Some(biome) => match biome {
+                Biome::Tundra => RGB::new(193, 225, 221).paint(),
+                Biome::BorealForest => RGB::new(165, 199, 144).paint(),
+                Biome::TemperateGrassland => RGB::new(252, 213, 122).paint(),
+                Biome::Woodland => RGB::new(209, 110, 63).paint(),
+                Biome::TemperateSeasonalForest => RGB::new(151, 182, 105).paint(),
+                Biome::TemperateRainForest => RGB::new(117, 169, 94).paint(),
+                Biome::SubtropicalDesert => RGB::new(220, 187, 80).paint(),
+                Biome::TropicalSeasonalForest => RGB::new(160, 151, 0).paint(),
+                Biome::TropicalRainForest => RGB::new(49, 122, 34).paint(),
+            },
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
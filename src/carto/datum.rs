@@ -192,6 +192,27 @@ impl DatumRe {
     }
 }
 
+/* # locating */
+
+/// anything pointing at a single datum of a brane
+pub trait Locate {
+    fn locate(self, resolution: Resolution) -> DatumZa;
+}
+
+impl Locate for DatumZa {
+    fn locate(self, resolution: Resolution) -> DatumZa {
+        let one: i32 = resolution.into();
+        self % one
+    }
+}
+
+impl Locate for DatumRe {
+    fn locate(self, resolution: Resolution) -> DatumZa {
+        let one: i32 = resolution.into();
+        self.find(resolution) % one
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
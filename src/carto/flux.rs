@@ -3,8 +3,14 @@ use crate::carto::{
     datum::DatumZa,
     honeycomb::HoneyCellToroidal,
 };
-use petgraph::graph::{Graph, NodeIndex};
-use std::{collections::HashMap, ops::Sub};
+use petgraph::{
+    graph::{Graph, NodeIndex, UnGraph},
+    Direction,
+};
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    ops::Sub,
+};
 
 pub struct Flux<T> {
     pub graph: Graph<DatumZa, T>,
@@ -12,12 +18,10 @@ pub struct Flux<T> {
     pub resolution: Resolution,
 }
 
-impl<T: Copy + PartialOrd + Sub<Output = T>> From<Brane<T>> for Flux<T> {
-    fn from(brane: Brane<T>) -> Self {
-        // this places roots at local minima
-        //    we could copy this thing with minimal changes to construct
-        //    a flux with roots at loacl maxima TODO
-
+impl<T: Copy + PartialOrd + Sub<Output = T>> Flux<T> {
+    /// connect every datum to its steepest neighbour
+    ///    going down when descending and up otherwise
+    fn construct(brane: &Brane<T>, descending: bool) -> Self {
         let mut graph = Graph::<DatumZa, T>::new();
         let mut nodes = HashMap::<DatumZa, NodeIndex>::new();
         let mut roots = Vec::<NodeIndex>::new();
@@ -26,22 +30,42 @@ impl<T: Copy + PartialOrd + Sub<Output = T>> From<Brane<T>> for Flux<T> {
             nodes.insert(datum, graph.add_node(datum));
         }
 
+        let beyond = |a: T, b: T| if descending { a < b } else { a > b };
+
         for jndex in 0..brane.resolution.square() {
             let datum = DatumZa::enravel(jndex, brane.resolution);
             match datum
                 .ambit_toroidal(brane.resolution.into())
                 .into_iter()
-                .filter(|source| brane.grid[source.unravel(brane.resolution)] < brane.grid[jndex])
+                .filter(|source| {
+                    beyond(
+                        brane.grid[source.unravel(brane.resolution)],
+                        brane.grid[jndex],
+                    )
+                })
                 .min_by(|a, b| {
-                    brane.grid[a.unravel(brane.resolution)]
+                    let ordering = brane.grid[a.unravel(brane.resolution)]
                         .partial_cmp(&brane.grid[b.unravel(brane.resolution)])
-                        .unwrap()
+                        .unwrap();
+                    if descending {
+                        ordering
+                    } else {
+                        ordering.reverse()
+                    }
                 }) {
                 Some(target) => {
+                    let (here, there) = (
+                        brane.grid[jndex],
+                        brane.grid[target.unravel(brane.resolution)],
+                    );
                     let _ = graph.add_edge(
                         nodes[&datum],
                         nodes[&target],
-                        brane.grid[jndex] - brane.grid[target.unravel(brane.resolution)],
+                        if descending {
+                            here - there
+                        } else {
+                            there - here
+                        },
                     );
                 }
                 None => roots.push(nodes[&datum]),
@@ -54,4 +78,192 @@ impl<T: Copy + PartialOrd + Sub<Output = T>> From<Brane<T>> for Flux<T> {
             resolution: brane.resolution,
         }
     }
+
+    /// places roots at local maxima, so that the flux follows ridge lines
+    pub fn inverted(brane: Brane<T>) -> Self {
+        Self::construct(&brane, false)
+    }
+
+    /// graph of basins, joined wherever they border each other
+    pub fn divides(&self, brane: &Brane<T>) -> UnGraph<usize, Divide<T>> {
+        let basins = self.basins();
+        let mut borders = BTreeMap::<(usize, usize), Divide<T>>::new();
+
+        for jndex in 0..self.resolution.square() {
+            let here = DatumZa::enravel(jndex, self.resolution);
+            for there in here.ambit_toroidal(self.resolution.into()) {
+                let kndex = there.unravel(self.resolution);
+                if basins.grid[jndex] < basins.grid[kndex] {
+                    // the crossing is as high as the higher of the two data
+                    let (pass, height) = if brane.grid[jndex] < brane.grid[kndex] {
+                        (there, brane.grid[kndex])
+                    } else {
+                        (here, brane.grid[jndex])
+                    };
+                    borders
+                        .entry((basins.grid[jndex], basins.grid[kndex]))
+                        .and_modify(|divide| {
+                            divide.border.push((here, there));
+                            if height < divide.height {
+                                divide.pass = pass;
+                                divide.height = height;
+                            }
+                        })
+                        .or_insert_with(|| Divide {
+                            border: vec![(here, there)],
+                            pass,
+                            height,
+                        });
+                }
+            }
+        }
+
+        let mut graph = UnGraph::<usize, Divide<T>>::new_undirected();
+        let nodes = (0..self.roots.len())
+            .map(|label| graph.add_node(label))
+            .collect::<Vec<NodeIndex>>();
+        for ((a, b), divide) in borders {
+            let _ = graph.add_edge(nodes[a], nodes[b], divide);
+        }
+        graph
+    }
+}
+
+impl<T: Copy + PartialOrd + Sub<Output = T>> From<Brane<T>> for Flux<T> {
+    fn from(brane: Brane<T>) -> Self {
+        // this places roots at local minima
+        Self::construct(&brane, true)
+    }
+}
+
+impl<T> Flux<T> {
+    /// nodes ordered so that every node comes after the one it flows into
+    pub fn breadth(&self) -> Vec<NodeIndex> {
+        let mut order = Vec::with_capacity(self.graph.node_count());
+        let mut queue = self.roots.iter().copied().collect::<VecDeque<NodeIndex>>();
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            queue.extend(self.graph.neighbors_directed(node, Direction::Incoming));
+        }
+        order
+    }
+
+    /// label every datum with the index of the root its flow ends in
+    pub fn basins(&self) -> Brane<usize> {
+        let mut basins = Brane::new(vec![0; self.resolution.square()], self.resolution);
+        for (label, root) in self.roots.iter().enumerate() {
+            let mut queue = VecDeque::from([*root]);
+            while let Some(node) = queue.pop_front() {
+                basins.grid[self.graph[node].unravel(self.resolution)] = label;
+                queue.extend(self.graph.neighbors_directed(node, Direction::Incoming));
+            }
+        }
+        basins
+    }
+
+    /// number of data draining through every datum, itself included
+    pub fn accumulation(&self) -> Brane<usize> {
+        let mut accumulation = Brane::new(vec![1; self.resolution.square()], self.resolution);
+        for node in self.breadth().into_iter().rev() {
+            let upstream = self
+                .graph
+                .neighbors_directed(node, Direction::Incoming)
+                .map(|source| accumulation.grid[self.graph[source].unravel(self.resolution)])
+                .sum::<usize>();
+            accumulation.grid[self.graph[node].unravel(self.resolution)] += upstream;
+        }
+        accumulation
+    }
+
+    /// data through which at least a threshold of data drain
+    ///    on an inverted flux these trace the ridge lines
+    pub fn channels(&self, threshold: usize) -> Brane<bool> {
+        self.accumulation()
+            .operate_by_value(|value| value >= threshold)
+    }
+}
+
+/// a border between two neighbouring basins
+pub struct Divide<T> {
+    /// pairs of neighbouring data on either side of the divide
+    pub border: Vec<(DatumZa, DatumZa)>,
+    /// lowest crossing of the divide
+    pub pass: DatumZa,
+    pub height: T,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    const RES: Resolution = Resolution::confine(6);
+
+    fn cone(peaks: &[DatumZa]) -> Brane<f64> {
+        Brane::create_by_index(RES, |j| {
+            peaks
+                .iter()
+                .map(|peak| DatumZa::enravel(j, RES).dist_toroidal(peak, RES.into()))
+                .min()
+                .unwrap() as f64
+        })
+    }
+
+    #[test]
+    fn flux_roots() {
+        let brane = cone(&[DatumZa::new(0, 0)]);
+        let flux = Flux::from(brane.clone());
+        assert_eq!(flux.roots.len(), 1);
+        assert_eq!(flux.graph[flux.roots[0]], DatumZa::new(0, 0));
+
+        let inverted = Flux::inverted(brane.clone());
+        assert!(!inverted.roots.is_empty());
+        for root in inverted.roots {
+            let datum = inverted.graph[root];
+            assert!(datum
+                .ambit_toroidal(RES.into())
+                .iter()
+                .all(|n| brane.grid[n.unravel(RES)] <= brane.grid[datum.unravel(RES)]));
+        }
+    }
+
+    #[test]
+    fn flux_accumulation() {
+        let flux = Flux::from(cone(&[DatumZa::new(0, 0)]));
+        let accumulation = flux.accumulation();
+        assert_eq!(accumulation.grid[0], RES.square());
+        assert!(flux.channels(RES.square()).grid[0]);
+        assert_eq!(
+            flux.channels(RES.square())
+                .grid
+                .iter()
+                .filter(|c| **c)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn flux_basins_divides() {
+        let brane = cone(&[DatumZa::new(0, 0), DatumZa::new(3, 3)]);
+        let flux = Flux::from(brane.clone());
+        assert_eq!(flux.roots.len(), 2);
+
+        let basins = flux.basins();
+        assert_ne!(
+            basins.grid[DatumZa::new(0, 0).unravel(RES)],
+            basins.grid[DatumZa::new(3, 3).unravel(RES)]
+        );
+
+        let divides = flux.divides(&brane);
+        assert_eq!(divides.node_count(), 2);
+        assert_eq!(divides.edge_count(), 1);
+        let divide = divides.edge_weights().next().unwrap();
+        assert!(!divide.border.is_empty());
+        assert!(divide
+            .border
+            .iter()
+            .all(|(a, b)| basins.grid[a.unravel(RES)] != basins.grid[b.unravel(RES)]));
+        assert!(divide.border.iter().all(|(a, b)| brane.grid[a.unravel(RES)]
+            .max(brane.grid[b.unravel(RES)])
+            >= divide.height));
+    }
 }
@@ -7,7 +7,7 @@ const SQRT3: f64 = 1.7320508;
 
 /* ## direction */
 
-#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum Direction {
     Xp,
     Zn,
@@ -1,8 +1,11 @@
-use crate::carto::{
-    brane::Brane,
-    colour::Ink,
-    datum::DatumZa,
-    honeycomb::{Hexagon, Tile, Tileable},
+use crate::{
+    carto::{
+        brane::{Brane, Resolution},
+        colour::Ink,
+        datum::DatumZa,
+        honeycomb::{Hexagon, Tile, Tileable},
+    },
+    units::Unit,
 };
 use geo::{
     orient::{Direction, Orient},
@@ -10,7 +13,10 @@ use geo::{
 };
 use geo_booleanop::boolean::BooleanOp;
 use log::trace;
-use std::collections::{HashMap, VecDeque};
+use std::{
+    collections::{HashMap, VecDeque},
+    fs,
+};
 use svg::node::element::Path;
 
 // try to replace goe-orient with something and then dump the geo dependency in favour of geo-types
@@ -73,6 +79,16 @@ pub trait Renderable<T> {
         S: Ink<T>;
 }
 
+/// offset moving a datum into the tile drawn at the origin
+fn tiling(datum: DatumZa, one: i32) -> DatumZa {
+    match datum.tile(one) {
+        Tile::Y => DatumZa::new(0, 0),
+        Tile::R => DatumZa::new(0, -one),
+        Tile::B => DatumZa::new(-one, 0),
+        Tile::G => DatumZa::new(-one, -one),
+    }
+}
+
 /// performs a union on a queue of polygons
 fn cascade(mut terrace: VecDeque<MultiPolygon<f64>>) -> MultiPolygon<f64> {
     // this can be less naive
@@ -97,12 +113,7 @@ impl<T: Clone + Copy> Renderable<T> for Brane<T> {
         let one: i32 = self.resolution.into();
         let mut terraces = HashMap::new();
         for datum in (0..self.resolution.square()).map(|j| DatumZa::enravel(j, self.resolution)) {
-            let tiling: DatumZa = match datum.tile(one) {
-                Tile::Y => DatumZa::new(0, 0),
-                Tile::R => DatumZa::new(0, -one),
-                Tile::B => DatumZa::new(-one, 0),
-                Tile::G => DatumZa::new(-one, -one),
-            };
+            let tiling = tiling(datum, one);
             terraces
                 .entry(ink.paint(self.grid[datum.unravel(self.resolution)]))
                 .or_insert_with(VecDeque::<MultiPolygon<f64>>::new)
@@ -135,6 +146,7 @@ impl<T: Clone + Copy> Renderable<T> for Brane<T> {
         }
 
         let path_name = format!("bounce/{}-{}.svg", variable, self.resolution.release());
+        fs::create_dir_all("bounce").unwrap();
         svg::save(&path_name, &image).unwrap();
     }
 
@@ -213,6 +225,49 @@ impl<T: Clone + Copy> Renderable<T> for Brane<T> {
             variable,
             self.resolution.release()
         );
+        fs::create_dir_all("bounce").unwrap();
         svg::save(&path_name, &image).unwrap();
     }
 }
+
+/* # rendering rivers */
+
+/// draw lines along courses of adjacent data, each segment with its own stroke width
+///     courses are unwrapped from the torus and start within the drawn tile
+pub fn render_rivers(
+    courses: &[Vec<DatumZa>],
+    widths: &[Vec<f64>],
+    resolution: Resolution,
+    variable: String,
+) {
+    trace!(
+        "rendering rivers {} at resolution {}",
+        variable,
+        resolution.release()
+    );
+    let one: i32 = resolution.into();
+
+    let mut image = svg::Document::new().set("viewBox", (-one, -one, 2 * one, 2 * one));
+    for (course, widths) in courses.iter().zip(widths.iter()) {
+        let tiling = tiling(course[0], one);
+        for (pair, width) in course.windows(2).zip(widths.iter()) {
+            let source = Coordinate::<f64>::from((pair[0] + tiling).centre());
+            let target = Coordinate::<f64>::from((pair[1] + tiling).centre());
+            image = image.add(
+                Path::new()
+                    .set(
+                        "d",
+                        format!("M{}L{}", coord_to_svg(&source), coord_to_svg(&target)),
+                    )
+                    .set("fill", "none")
+                    .set("stroke", "rgb(84, 144, 184)")
+                    .set("stroke-linecap", "round")
+                    .set("stroke-width", *width),
+            );
+        }
+    }
+
+    let path_name = format!("bounce/{}-{}.svg", variable, resolution.release());
+    fs::create_dir_all("bounce").unwrap();
+    svg::save(&path_name, &image).unwrap();
+}
@@ -1,4 +1,19 @@
+pub mod atmosphere;
 pub mod chart;
 pub mod circulation;
+pub mod classifier;
 pub mod cosmos;
+pub mod cryosphere;
+pub mod diagram;
+pub mod glacier;
+pub mod holdridge;
+pub mod hydrograph;
+pub mod koppen;
+pub mod moisture;
+pub mod month;
+pub mod ocean;
+pub mod orbit;
+pub mod river;
+pub mod scenario;
 pub mod vegetation;
+pub mod whittaker;
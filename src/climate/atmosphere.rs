@@ -0,0 +1,176 @@
+use crate::{
+    carto::{
+        brane::Brane,
+        datum::{DatumRe, DatumZa},
+        honeycomb::{Direction, Hexagon, HoneyCellPlanar, HoneyCellToroidal},
+    },
+    units::{Elevation, Temperature, Unit},
+};
+use std::f64::consts::TAU;
+
+#[derive(Clone, Debug)]
+pub struct Atmosphere {
+    /// how strongly warm air lowers the pressure
+    pub thermal: f64,
+    /// how strongly altitude lowers the pressure
+    pub orographic: f64,
+    /// angle by which winds turn right of the pressure gradient, if at all
+    pub coriolis: Option<f64>,
+}
+
+impl Default for Atmosphere {
+    fn default() -> Self {
+        Self {
+            thermal: 1.,
+            orographic: 0.24,
+            coriolis: Some(TAU * 12f64.recip()),
+        }
+    }
+}
+
+/// prevailing wind at a datum
+#[derive(Clone, Copy, Debug)]
+pub struct Wind {
+    pub direction: Direction,
+    pub magnitude: f64,
+}
+
+impl Wind {
+    /// share of the wind blowing towards a given direction
+    pub fn towards(&self, direction: Direction) -> f64 {
+        let turn = (direction.index() + 6 - self.direction.index()) % 6;
+        self.magnitude * (TAU * turn as f64 / 6.).cos().max(0.)
+    }
+
+    /// wind as a vector in hexagon centre coordinates
+    pub fn vector(&self) -> DatumRe {
+        heading(self.direction) * self.magnitude
+    }
+}
+
+/// unit vector pointing to the neighbour in a given direction
+pub fn heading(direction: Direction) -> DatumRe {
+    let origin = DatumZa::new(0, 0);
+    let step = origin.neighbour_planar(direction).centre() - origin.centre();
+    step / (step.x.powi(2) + step.y.powi(2)).sqrt()
+}
+
+/// surface pressure, low over warm air and high ground
+pub fn pressure(
+    temperature: &Brane<Temperature>,
+    altitude_above_ocean: &Brane<Elevation>,
+    params: &Atmosphere,
+) -> Brane<f64> {
+    let resolution = temperature.resolution;
+    let mean = temperature
+        .grid
+        .iter()
+        .map(|value| value.release())
+        .sum::<f64>()
+        / resolution.square() as f64;
+    temperature.operate_by_index(|j| {
+        1. - params.thermal * (temperature.grid[j].release() - mean)
+            - params.orographic * altitude_above_ocean.grid[j].release().max(0.)
+    })
+}
+
+/// winds blowing down the pressure gradient, turned by the rotation of the world
+pub fn winds(pressure: &Brane<f64>, params: &Atmosphere) -> Brane<Wind> {
+    let resolution = pressure.resolution;
+    let one: i32 = resolution.into();
+    let turn = params.coriolis.unwrap_or(0.);
+    pressure.operate_by_index(|j| {
+        let here = DatumZa::enravel(j, resolution);
+        let flow = Direction::array()
+            .iter()
+            .map(|direction| {
+                heading(*direction)
+                    * (pressure.grid[j]
+                        - pressure.grid
+                            [here.neighbour_toroidal(*direction, one).unravel(resolution)])
+            })
+            .fold(DatumRe::new(0., 0.), |a, b| a + b)
+            * f64::from(resolution);
+        let flow = DatumRe::new(
+            flow.x * turn.cos() + flow.y * turn.sin(),
+            -flow.x * turn.sin() + flow.y * turn.cos(),
+        );
+        let direction = *Direction::array()
+            .iter()
+            .max_by(|a, b| {
+                let along = |d: &Direction| {
+                    let h = heading(*d);
+                    h.x * flow.x + h.y * flow.y
+                };
+                along(a)
+                    .partial_cmp(&along(b))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .expect("there are six directions");
+        Wind {
+            direction,
+            magnitude: (flow.x.powi(2) + flow.y.powi(2)).sqrt(),
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::carto::brane::Resolution;
+    const RES: Resolution = Resolution::confine(12);
+
+    #[test]
+    fn wind_blows_from_cold_to_warm() {
+        let warm = DatumZa::new(6, 6);
+        let temperature = Brane::create_by_index(RES, |j| {
+            Temperature::confine(
+                1. - DatumZa::enravel(j, RES).dist_toroidal(&warm, RES.into()) as f64 / 12.,
+            )
+        });
+        let altitude = Brane::create_by_index(RES, |_| Elevation::confine(0.));
+        let params = Atmosphere {
+            coriolis: None,
+            ..Atmosphere::default()
+        };
+        let winds = winds(&pressure(&temperature, &altitude, &params), &params);
+        for direction in Direction::array() {
+            let upwind = warm.neighbour_toroidal(direction, RES.into());
+            let wind = winds.grid[upwind.unravel(RES)];
+            // blowing back towards the warm datum
+            assert_eq!(
+                wind.direction,
+                Direction::array()[(direction.index() + 3) % 6]
+            );
+            assert!(wind.magnitude > 0.);
+        }
+    }
+
+    #[test]
+    fn coriolis_turns_winds() {
+        // warming steadily in one direction
+        let temperature = Brane::create_by_index(RES, |j| {
+            let centre = DatumZa::enravel(j, RES).centre();
+            let along = heading(Direction::Xp);
+            Temperature::confine(0.5 + 0.02 * (centre.x * along.x + centre.y * along.y))
+        });
+        let altitude = Brane::create_by_index(RES, |_| Elevation::confine(0.));
+        let straight = Atmosphere {
+            coriolis: None,
+            ..Atmosphere::default()
+        };
+        let turned = Atmosphere {
+            coriolis: Some(TAU / 6.),
+            ..Atmosphere::default()
+        };
+        let j = DatumZa::new(3, 3).unravel(RES);
+        let pressure = pressure(&temperature, &altitude, &straight);
+        let a = winds(&pressure, &straight).grid[j];
+        let b = winds(&pressure, &turned).grid[j];
+        assert_eq!(a.direction, Direction::Xp);
+        assert_eq!(b.direction, Direction::Zn);
+        assert!((a.magnitude - b.magnitude).abs() < 0.000001);
+        assert!((a.towards(Direction::Xp) - a.magnitude).abs() < 0.000001);
+        assert_eq!(a.towards(Direction::Xn), 0.);
+    }
+}
@@ -1,21 +1,41 @@
-use crate::units::{Precipitation, Temperature, Unit};
+use crate::{
+    climate::cryosphere::snow_fraction,
+    units::{Precipitation, Temperature, Unit},
+};
+use std::cmp::Ordering;
 
 pub struct Zone {
     pub thermos: Temperature,
     pub igros: f64,
     pub parallaxos: f64,
+    /// mean temperature of the warmest month
+    pub warmest: Temperature,
+    /// fraction of days with frost
+    pub frost: f64,
 }
 
 impl Zone {
     pub fn is_nan(&self) -> bool {
-        self.thermos.release().is_nan() || self.igros.is_nan() || self.parallaxos.is_nan()
+        self.thermos.release().is_nan()
+            || self.igros.is_nan()
+            || self.parallaxos.is_nan()
+            || self.warmest.release().is_nan()
+            || self.frost.is_nan()
     }
 
-    pub fn new(thermos: Temperature, igros: f64, parallaxos: f64) -> Self {
+    pub fn new(
+        thermos: Temperature,
+        igros: f64,
+        parallaxos: f64,
+        warmest: Temperature,
+        frost: f64,
+    ) -> Self {
         Self {
             thermos,
             igros,
             parallaxos,
+            warmest,
+            frost,
         }
     }
 }
@@ -26,47 +46,293 @@ impl From<&Chart> for Zone {
             thermos: chart.thermos(),
             igros: chart.igros(),
             parallaxos: chart.parallaxos(),
+            warmest: chart.warmest(),
+            frost: chart.frost(),
         }
     }
 }
 
+/// monthly soil water budget of a chart
+pub struct WaterBalance {
+    /// water held in the soil at the end of every month
+    pub storage: Vec<Precipitation>,
+    /// actual evapotranspiration
+    pub actual: Vec<Precipitation>,
+    /// water the full soil cannot hold, running off
+    pub surplus: Vec<Precipitation>,
+    /// demand of the air the soil cannot meet
+    pub deficit: Vec<Precipitation>,
+}
+
+impl WaterBalance {
+    fn total(values: &[Precipitation]) -> Precipitation {
+        values
+            .iter()
+            .copied()
+            .fold(Precipitation::confine(0.), |a, b| a + b)
+    }
+
+    pub fn annual_actual(&self) -> Precipitation {
+        Self::total(&self.actual)
+    }
+
+    pub fn annual_surplus(&self) -> Precipitation {
+        Self::total(&self.surplus)
+    }
+
+    pub fn annual_deficit(&self) -> Precipitation {
+        Self::total(&self.deficit)
+    }
+}
+
+/// climate indices, each a single number for a chart
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Index {
+    /// milimeters over the year
+    AnnualRainfall,
+    /// milimeters over the warmest half of the year
+    SummerRainfall,
+    /// milimeters over the coldest half of the year
+    WinterRainfall,
+    /// degrees celcius between the warmest and coldest month
+    TemperatureRange,
+    /// degrees celcius of the warmest month
+    Warmest,
+    /// degrees celcius of the coldest month
+    Coldest,
+    /// degree days above five degrees celcius
+    GrowingDegreeDays,
+    /// months of twelve without frost
+    FrostFreeMonths,
+    /// coefficient of variation of monthly rainfall
+    RainfallVariation,
+    /// aridity index of de martonne
+    DeMartonne,
+    /// milimeters over the driest quarter of the year
+    DriestQuarter,
+}
+
 #[derive(Clone)]
 pub struct Chart {
+    months: usize,
     temperature: Vec<Temperature>,
+    temperature_min: Vec<Temperature>,
+    temperature_max: Vec<Temperature>,
     rainfall: Vec<Precipitation>,
     evaporation: Vec<Precipitation>,
+    /// water holding capacity of the soil
+    capacity: Precipitation,
+    /// water equivalent of the snow lying at the end of every month
+    snow: Vec<Precipitation>,
 }
 
 impl Chart {
-    pub fn empty() -> Self {
+    /// chart to be filled with a given number of months
+    pub fn empty(months: usize) -> Self {
         Self {
-            temperature: Vec::new(),
-            rainfall: Vec::new(),
-            evaporation: Vec::new(),
+            months,
+            temperature: Vec::with_capacity(months),
+            temperature_min: Vec::with_capacity(months),
+            temperature_max: Vec::with_capacity(months),
+            rainfall: Vec::with_capacity(months),
+            evaporation: Vec::with_capacity(months),
+            capacity: Precipitation::confine(0.),
+            snow: Vec::new(),
         }
     }
 
     pub fn new(
         temperature: Vec<Temperature>,
+        temperature_min: Vec<Temperature>,
+        temperature_max: Vec<Temperature>,
         rainfall: Vec<Precipitation>,
         evaporation: Vec<Precipitation>,
     ) -> Self {
+        let months = temperature.len();
+        assert!([
+            temperature_min.len(),
+            temperature_max.len(),
+            rainfall.len(),
+            evaporation.len()
+        ]
+        .iter()
+        .all(|len| *len == months));
         Self {
+            months,
             temperature,
+            temperature_min,
+            temperature_max,
             rainfall,
             evaporation,
+            capacity: Precipitation::confine(0.),
+            snow: Vec::new(),
         }
     }
 
+    /// chart with soil holding up to a given amount of water
+    pub fn with_soil(self, capacity: Precipitation) -> Self {
+        Self { capacity, ..self }
+    }
+
+    /// water holding capacity of the soil
+    pub fn capacity(&self) -> Precipitation {
+        self.capacity
+    }
+
+    /// chart with snow lying at the end of every month, as the cryosphere left it
+    pub fn with_snow(self, snow: Vec<Precipitation>) -> Self {
+        assert_eq!(snow.len(), self.months, "snow is needed for every month");
+        Self { snow, ..self }
+    }
+
+    /// water equivalent of the snow lying at the end of every month
+    pub fn snow(&self) -> &[Precipitation] {
+        &self.snow
+    }
+
+    /// record another month
+    ///     a chart already holding every month lengthens its year
     pub fn push(
         &mut self,
         temperature: Temperature,
+        temperature_min: Temperature,
+        temperature_max: Temperature,
         rainfall: Precipitation,
         evaporation: Precipitation,
     ) {
         self.temperature.push(temperature);
+        self.temperature_min.push(temperature_min);
+        self.temperature_max.push(temperature_max);
         self.rainfall.push(rainfall);
         self.evaporation.push(evaporation);
+        self.months = self.months.max(self.temperature.len());
+    }
+
+    /// number of months in a year
+    pub fn months(&self) -> usize {
+        self.months
+    }
+
+    /// mean temperature of every month
+    pub fn temperature(&self) -> &[Temperature] {
+        &self.temperature
+    }
+
+    /// mean daily minimum temperature of every month
+    pub fn temperature_min(&self) -> &[Temperature] {
+        &self.temperature_min
+    }
+
+    /// rainfall of every month
+    pub fn rainfall(&self) -> &[Precipitation] {
+        &self.rainfall
+    }
+
+    /// potential evaporation of every month
+    pub fn evaporation(&self) -> &[Precipitation] {
+        &self.evaporation
+    }
+
+    /// whether every month of the year has been recorded
+    pub fn is_complete(&self) -> bool {
+        self.temperature.len() == self.months
+    }
+
+    /* # soil moisture */
+
+    /// thornthwaite-mather bucket run over the year until it repeats itself
+    ///     the soil fills when rain exceeds the potential evaporation
+    ///     and dries out exponentially as it gets emptier otherwise
+    pub fn water_balance(&self) -> WaterBalance {
+        self.budget(&self.rainfall)
+    }
+
+    /// soil water budget with snow held back until it melts
+    pub fn water_balance_with_snow(&self) -> WaterBalance {
+        self.budget(&self.meltwater())
+    }
+
+    /// rain and melted snow reaching the ground in every month
+    ///     whatever the snow cover gains is held back, whatever it loses is released
+    ///     and charts without snow let all of the rain through
+    ///     a cover gaining more than falls takes the rest from the following months
+    pub fn meltwater(&self) -> Vec<Precipitation> {
+        let pack = |month: usize| self.snow.get(month).map_or(0., |snow| snow.release());
+        let change = self
+            .rainfall
+            .iter()
+            .enumerate()
+            .map(|(month, rain)| {
+                rain.release() - pack(month) + pack((month + self.months - 1) % self.months)
+            })
+            .collect::<Vec<f64>>();
+
+        // the debt carried over the turn of the year settles after a few years
+        let mut debt = 0.;
+        for _ in 0..12 {
+            let start = debt;
+            for water in change.iter() {
+                debt = (debt - water).max(0.);
+            }
+            if (debt - start).abs() <= f64::EPSILON {
+                break;
+            }
+        }
+
+        change
+            .iter()
+            .map(|water| {
+                let liquid = water - debt;
+                debt = (-liquid).max(0.);
+                Precipitation::confine(liquid.max(0.))
+            })
+            .collect()
+    }
+
+    fn budget(&self, supply: &[Precipitation]) -> WaterBalance {
+        let capacity = self.capacity.release();
+        let step = |storage: f64, rain: f64, demand: f64| -> (f64, f64, f64) {
+            // returns storage, actual evaporation and surplus
+            if rain >= demand {
+                let wet = storage + rain - demand;
+                (wet.min(capacity), demand, (wet - capacity).max(0.))
+            } else if capacity > 0. {
+                let dry = storage * (-(demand - rain) / capacity).exp();
+                (dry, rain + storage - dry, 0.)
+            } else {
+                (0., rain, 0.)
+            }
+        };
+
+        // the storage at the turn of the year settles after a few years
+        let mut storage = capacity;
+        for _ in 0..12 {
+            let start = storage;
+            for (rain, demand) in supply.iter().zip(self.evaporation.iter()) {
+                storage = step(storage, rain.release(), demand.release()).0;
+            }
+            if (storage - start).abs() <= capacity * 1e-6 {
+                break;
+            }
+        }
+
+        let mut balance = WaterBalance {
+            storage: Vec::with_capacity(self.months),
+            actual: Vec::with_capacity(self.months),
+            surplus: Vec::with_capacity(self.months),
+            deficit: Vec::with_capacity(self.months),
+        };
+        for (rain, demand) in supply.iter().zip(self.evaporation.iter()) {
+            let (next, actual, surplus) = step(storage, rain.release(), demand.release());
+            storage = next;
+            balance.storage.push(Precipitation::confine(storage));
+            balance.actual.push(Precipitation::confine(actual));
+            balance.surplus.push(Precipitation::confine(surplus));
+            balance
+                .deficit
+                .push(Precipitation::confine(demand.release() - actual));
+        }
+        balance
     }
 
     /* # climatological indices */
@@ -84,12 +350,200 @@ impl Chart {
             / self.temperature.len() as f64
     }
 
-    pub fn igros(&self) -> f64 {
-        let moonly = self
+    /// mean temperature of the warmest month
+    pub fn warmest(&self) -> Temperature {
+        self.temperature
+            .iter()
+            .copied()
+            .reduce(|a, b| if b > a { b } else { a })
+            .unwrap_or_else(|| Temperature::confine(f64::NAN))
+    }
+
+    /// mean temperature of the coldest month
+    pub fn coldest(&self) -> Temperature {
+        self.temperature
+            .iter()
+            .copied()
+            .reduce(|a, b| if b < a { b } else { a })
+            .unwrap_or_else(|| Temperature::confine(f64::NAN))
+    }
+
+    /// degrees celcius between the warmest and the coldest month
+    pub fn temperature_range(&self) -> f64 {
+        self.warmest().celcius() - self.coldest().celcius()
+    }
+
+    /// whether every month belongs to the warmest half of the year
+    ///     there are no hemispheres to tell summer from winter
+    pub fn summer(&self) -> Vec<bool> {
+        let months = self.temperature.len();
+        let half = (months / 2).max(1);
+        let warmth = |start: usize| -> f64 {
+            (start..start + half)
+                .map(|month| self.temperature[month % months].release())
+                .sum::<f64>()
+        };
+        let start = (0..months)
+            .max_by(|a, b| {
+                warmth(*a)
+                    .partial_cmp(&warmth(*b))
+                    .unwrap_or(Ordering::Equal)
+            })
+            .unwrap_or(0);
+        (0..months)
+            .map(|month| (month + months - start) % months < half)
+            .collect()
+    }
+
+    fn seasonal_rainfall(&self, in_summer: bool) -> Precipitation {
+        self.rainfall
+            .iter()
+            .zip(self.summer())
+            .filter(|(_, summer)| *summer == in_summer)
+            .fold(Precipitation::confine(0.), |a, (b, _)| a + *b)
+            * (12. / self.months.max(1) as f64)
+    }
+
+    /// rainfall over the warmest half of a year of twelve months
+    pub fn summer_rainfall(&self) -> Precipitation {
+        self.seasonal_rainfall(true)
+    }
+
+    /// rainfall over the coldest half of a year of twelve months
+    pub fn winter_rainfall(&self) -> Precipitation {
+        self.seasonal_rainfall(false)
+    }
+
+    /// rainfall over the driest quarter of a year of twelve months
+    pub fn driest_quarter(&self) -> Precipitation {
+        let months = self.rainfall.len();
+        let quarter = (months / 4).max(1);
+        let driest = (0..months)
+            .map(|start| {
+                (start..start + quarter)
+                    .map(|month| self.rainfall[month % months].release())
+                    .sum::<f64>()
+            })
+            .fold(f64::NAN, f64::min);
+        Precipitation::confine(driest * 12. / months as f64)
+    }
+
+    /// degree days of the year above a base temperature in degrees celcius
+    pub fn growing_degree_days(&self, base: f64) -> f64 {
+        self.temperature
+            .iter()
+            .map(|t| (t.celcius() - base).max(0.))
+            .sum::<f64>()
+            * 365.25
+            / self.months.max(1) as f64
+    }
+
+    /// months of twelve with the mean daily minimum above freezing
+    pub fn frost_free_months(&self) -> f64 {
+        self.temperature_min
+            .iter()
+            .filter(|t| t.celcius() > 0.)
+            .count() as f64
+            * 12.
+            / self.months.max(1) as f64
+    }
+
+    /// standard deviation of monthly rainfall over its mean
+    ///     a place without any rain does not vary at all
+    pub fn rainfall_variation(&self) -> f64 {
+        let months = self.rainfall.len() as f64;
+        let mean = self.rainfall.iter().map(|r| r.release()).sum::<f64>() / months;
+        let variance = self
             .rainfall
             .iter()
+            .map(|r| (r.release() - mean).powi(2))
+            .sum::<f64>()
+            / months;
+        match mean > 0. {
+            true => variance.sqrt() / mean,
+            false => 0.,
+        }
+    }
+
+    /// annual rainfall in milimeters over the mean temperature plus ten degrees
+    ///     lower for drier places, below ten counts as arid
+    ///     the index breaks down in the bitter cold, so it is undefined below minus nine degrees
+    pub fn de_martonne(&self) -> f64 {
+        let denominator = self.thermos().celcius() + 10.;
+        match denominator >= 1. {
+            true => self.annual_rainfall().release() * 324. / denominator,
+            false => f64::NAN,
+        }
+    }
+
+    /// value of any index
+    pub fn index(&self, index: Index) -> f64 {
+        let milimeters = |precipitation: Precipitation| precipitation.release() * 324.;
+        match index {
+            Index::AnnualRainfall => milimeters(self.annual_rainfall()),
+            Index::SummerRainfall => milimeters(self.summer_rainfall()),
+            Index::WinterRainfall => milimeters(self.winter_rainfall()),
+            Index::TemperatureRange => self.temperature_range(),
+            Index::Warmest => self.warmest().celcius(),
+            Index::Coldest => self.coldest().celcius(),
+            Index::GrowingDegreeDays => self.growing_degree_days(5.),
+            Index::FrostFreeMonths => self.frost_free_months(),
+            Index::RainfallVariation => self.rainfall_variation(),
+            Index::DeMartonne => self.de_martonne(),
+            Index::DriestQuarter => milimeters(self.driest_quarter()),
+        }
+    }
+
+    /// rainfall over a year of twelve months
+    pub fn annual_rainfall(&self) -> Precipitation {
+        self.rainfall
+            .iter()
+            .copied()
+            .fold(Precipitation::confine(0.), |a, b| a + b)
+            * (12. / self.months.max(1) as f64)
+    }
+
+    /// precipitation falling as snow over the year
+    pub fn snowfall(&self) -> Precipitation {
+        Precipitation::confine(
+            self.temperature
+                .iter()
+                .zip(self.rainfall.iter())
+                .map(|(t, r)| r.release() * snow_fraction(t.celcius()))
+                .sum::<f64>(),
+        )
+    }
+
+    /// sum of monthly temperatures above freezing, in degrees celcius
+    ///     with every month cooled by a given offset
+    pub fn positive_degree_months(&self, cooling: f64) -> f64 {
+        self.temperature
+            .iter()
+            .map(|t| (t.celcius() - cooling).max(0.))
+            .sum::<f64>()
+    }
+
+    /// fraction of days with frost
+    pub fn frost(&self) -> f64 {
+        // daily minima scatter around their monthly mean by a few degrees
+        let spread = 4.;
+        self.temperature_min
+            .iter()
+            .map(|t| (1. + (1.7 * t.celcius() / spread).exp()).recip())
+            .sum::<f64>()
+            / self.temperature_min.len() as f64
+    }
+
+    /// wetness of the year, with water stored in the soil carried into dry months
+    ///     without soil this compares rainfall to potential evaporation month by month
+    pub fn igros(&self) -> f64 {
+        let balance = self.water_balance();
+        let moonly = balance
+            .actual
+            .iter()
+            .zip(balance.surplus.iter())
             .zip(self.evaporation.iter())
-            .map(|(r, p)| Self::deficiency(r.release(), p.release()))
+            .map(|((a, s), p)| Self::deficiency(a.release() + s.release(), p.release()))
             .collect::<Vec<f64>>();
         moonly.iter().sum::<f64>() / moonly.len() as f64
     }
@@ -119,38 +573,319 @@ mod test {
 
     #[test]
     fn zone_from_chart() {
-        let z0 = Zone::new(Temperature::confine(1.), 1., 0.321655);
+        let z0 = Zone::new(
+            Temperature::confine(1.),
+            1.,
+            0.321655,
+            Temperature::confine(2.),
+            0.,
+        );
         let z1 = Zone::from(&Chart {
+            months: 2,
             temperature: Vec::from([Temperature::confine(1.), Temperature::confine(2.)]),
+            temperature_min: Vec::from([Temperature::confine(1.), Temperature::confine(2.)]),
+            temperature_max: Vec::from([Temperature::confine(1.), Temperature::confine(2.)]),
             rainfall: Vec::from([Precipitation::confine(1.), Precipitation::confine(3.)]),
             evaporation: Vec::from([Precipitation::confine(1.), Precipitation::confine(3.)]),
+            capacity: Precipitation::confine(0.),
+            snow: Vec::new(),
         });
         assert_float_eq!(z0.thermos.release(), z1.thermos.release(), abs <= EPSILON);
         assert_float_eq!(z0.igros, z1.igros, abs <= EPSILON);
         assert_float_eq!(z0.parallaxos, z1.parallaxos, abs <= EPSILON);
+        assert_float_eq!(z0.warmest.release(), z1.warmest.release(), abs <= EPSILON);
+        assert_float_eq!(z0.frost, z1.frost, abs <= EPSILON);
+    }
+
+    #[test]
+    fn frost_from_minima() {
+        let chart = |min: f64| {
+            Chart::new(
+                vec![Temperature::from_celcius(min + 6.); 4],
+                vec![Temperature::from_celcius(min); 4],
+                vec![Temperature::from_celcius(min + 12.); 4],
+                vec![Precipitation::confine(1.); 4],
+                vec![Precipitation::confine(1.); 4],
+            )
+        };
+        assert!(chart(-24.).frost() > 0.99);
+        assert!(chart(24.).frost() < 0.01);
+        assert_float_eq!(chart(0.).frost(), 0.5, abs <= EPSILON);
+    }
+
+    #[test]
+    fn soil_carries_water_into_dry_months() {
+        let chart = |capacity: f64| {
+            Chart::new(
+                vec![Temperature::from_celcius(18.); 4],
+                vec![Temperature::from_celcius(12.); 4],
+                vec![Temperature::from_celcius(24.); 4],
+                [1.6, 1.6, 0., 0.]
+                    .into_iter()
+                    .map(Precipitation::confine)
+                    .collect(),
+                vec![Precipitation::confine(0.4); 4],
+            )
+            .with_soil(Precipitation::confine(capacity))
+        };
+        let bare = chart(0.).water_balance();
+        let deep = chart(0.6).water_balance();
+        // water is conserved over the year
+        for balance in [&bare, &deep] {
+            assert_float_eq!(
+                (balance.annual_actual() + balance.annual_surplus()).release(),
+                3.2,
+                abs <= EPSILON
+            );
+        }
+        assert_float_eq!(bare.annual_surplus().release(), 2.4, abs <= EPSILON);
+        assert!(deep.annual_surplus() < bare.annual_surplus());
+        assert!(deep.actual[2] > bare.actual[2]);
+        assert!(deep.storage[2] < deep.storage[1]);
+        assert!(chart(0.6).igros() > chart(0.).igros());
+    }
+
+    #[test]
+    fn igros_of_known_charts() {
+        let chart = |rain: [f64; 4], capacity: f64| {
+            Chart::new(
+                vec![Temperature::from_celcius(18.); 4],
+                vec![Temperature::from_celcius(12.); 4],
+                vec![Temperature::from_celcius(24.); 4],
+                rain.into_iter().map(Precipitation::confine).collect(),
+                vec![Precipitation::confine(0.2); 4],
+            )
+            .with_soil(Precipitation::confine(capacity))
+        };
+        // without soil actual evaporation and surplus add up to the rain,
+        //     so the thresholds keep the meaning they had for rainfall alone
+        let dry = chart([0.05, 0.1, 0.2, 0.15], 0.);
+        assert_float_eq!(dry.igros(), (-0.75 - 0.5 + 0. - 0.25) / 4., abs <= EPSILON);
+        // the soil moves water into the dry month without changing the yearly mean
+        let seasonal = chart([0.1, 0.2, 0.4, 0.3], 0.1);
+        let balance = seasonal.water_balance();
+        assert_float_eq!(
+            balance.actual[0].release(),
+            0.2 - 0.1 * (-1f64).exp(),
+            abs <= EPSILON
+        );
+        assert_float_eq!(seasonal.igros(), 0.25, abs <= EPSILON);
+        assert_float_eq!(
+            seasonal.igros(),
+            chart([0.1, 0.2, 0.4, 0.3], 0.).igros(),
+            abs <= EPSILON
+        );
+    }
+
+    #[test]
+    fn snow_melts_in_spring() {
+        let chart = Chart::new(
+            [-12., -6., 6., 12.]
+                .into_iter()
+                .map(Temperature::from_celcius)
+                .collect(),
+            vec![Temperature::from_celcius(0.); 4],
+            vec![Temperature::from_celcius(0.); 4],
+            vec![Precipitation::confine(0.2); 4],
+            vec![Precipitation::confine(0.1); 4],
+        );
+        assert_eq!(chart.meltwater(), chart.rainfall);
+        let chart = chart.with_snow(
+            [0.2, 0.4, 0., 0.]
+                .into_iter()
+                .map(Precipitation::confine)
+                .collect(),
+        );
+        let liquid = chart.meltwater();
+        assert_float_eq!(liquid[0].release(), 0., abs <= EPSILON);
+        assert_float_eq!(liquid[1].release(), 0., abs <= EPSILON);
+        assert_float_eq!(liquid[2].release(), 0.6, abs <= EPSILON);
+        assert_float_eq!(
+            liquid.iter().map(|l| l.release()).sum::<f64>(),
+            0.8,
+            abs <= EPSILON
+        );
+        assert!(
+            chart.water_balance_with_snow().annual_actual()
+                <= chart.water_balance().annual_actual()
+        );
+    }
+
+    #[test]
+    fn snow_debt_carries_over() {
+        // the cover gains more in the second month than falls, and melts in the last
+        let chart = Chart::new(
+            vec![Temperature::from_celcius(0.); 4],
+            vec![Temperature::from_celcius(0.); 4],
+            vec![Temperature::from_celcius(0.); 4],
+            vec![Precipitation::confine(0.2); 4],
+            vec![Precipitation::confine(0.1); 4],
+        )
+        .with_snow(
+            [0.1, 0.5, 0.5, 0.]
+                .into_iter()
+                .map(Precipitation::confine)
+                .collect(),
+        );
+        let liquid = chart.meltwater();
+        assert_float_eq!(liquid[0].release(), 0.1, abs <= EPSILON);
+        assert_float_eq!(liquid[1].release(), 0., abs <= EPSILON);
+        assert_float_eq!(liquid[2].release(), 0., abs <= EPSILON);
+        assert_float_eq!(liquid[3].release(), 0.7, abs <= EPSILON);
+        assert_float_eq!(
+            liquid.iter().map(|l| l.release()).sum::<f64>(),
+            0.8,
+            abs <= EPSILON
+        );
+    }
+
+    #[test]
+    fn climate_indices() {
+        let chart = Chart::new(
+            [0., 10., 20., 10.]
+                .into_iter()
+                .map(Temperature::from_celcius)
+                .collect(),
+            [-6., 4., 14., 4.]
+                .into_iter()
+                .map(Temperature::from_celcius)
+                .collect(),
+            [6., 16., 26., 16.]
+                .into_iter()
+                .map(Temperature::from_celcius)
+                .collect(),
+            [0.1, 0.2, 0.4, 0.3]
+                .into_iter()
+                .map(Precipitation::confine)
+                .collect(),
+            vec![Precipitation::confine(0.2); 4],
+        );
+        assert_float_eq!(chart.index(Index::AnnualRainfall), 972., rmax <= EPSILON);
+        assert_float_eq!(chart.index(Index::TemperatureRange), 20., abs <= EPSILON);
+        assert_float_eq!(chart.index(Index::Coldest), 0., abs <= EPSILON);
+        assert_float_eq!(chart.index(Index::FrostFreeMonths), 9., abs <= EPSILON);
+        assert_float_eq!(
+            chart.index(Index::DriestQuarter),
+            0.1 * 324. * 3.,
+            rmax <= EPSILON
+        );
+        assert_float_eq!(
+            chart.index(Index::SummerRainfall) + chart.index(Index::WinterRainfall),
+            972.,
+            rmax <= EPSILON
+        );
+        assert!(chart.index(Index::SummerRainfall) > chart.index(Index::WinterRainfall));
+        assert_float_eq!(
+            chart.index(Index::GrowingDegreeDays),
+            (5. + 15. + 5.) * 365.25 / 4.,
+            rmax <= EPSILON
+        );
+        assert_float_eq!(chart.index(Index::DeMartonne), 972. / 20., rmax <= EPSILON);
+        assert!(chart.index(Index::RainfallVariation) > 0.);
+    }
+
+    #[test]
+    fn climate_indices_at_extremes() {
+        let chart = |celcius: f64, rain: f64| {
+            Chart::new(
+                vec![Temperature::from_celcius(celcius); 4],
+                vec![Temperature::from_celcius(celcius - 4.); 4],
+                vec![Temperature::from_celcius(celcius + 4.); 4],
+                vec![Precipitation::confine(rain); 4],
+                vec![Precipitation::confine(0.); 4],
+            )
+        };
+        assert_float_eq!(
+            chart(20., 0.).index(Index::RainfallVariation),
+            0.,
+            abs <= EPSILON
+        );
+        assert_float_eq!(chart(20., 0.).index(Index::DeMartonne), 0., abs <= EPSILON);
+        assert!(chart(-8., 0.1).index(Index::DeMartonne) > 0.);
+        assert!(chart(-20., 0.1).index(Index::DeMartonne).is_nan());
     }
 
     #[test]
     fn zone_from_empty_chart() {
-        let z = Zone::from(&Chart::empty());
+        let z = Zone::from(&Chart::empty(0));
         assert!(z.thermos.release().is_nan());
         assert!(z.igros.is_nan());
         assert!(z.parallaxos.is_nan());
+        assert!(z.frost.is_nan());
         assert!(z.is_nan());
     }
 
     #[test]
     fn push() {
         let mut chart = Chart {
+            months: 3,
             temperature: Vec::from([Temperature::confine(1.), Temperature::confine(2.)]),
+            temperature_min: Vec::from([Temperature::confine(0.), Temperature::confine(1.)]),
+            temperature_max: Vec::from([Temperature::confine(2.), Temperature::confine(3.)]),
             rainfall: Vec::from([Precipitation::confine(1.), Precipitation::confine(2.)]),
             evaporation: Vec::from([Precipitation::confine(1.), Precipitation::confine(2.)]),
+            capacity: Precipitation::confine(0.),
+            snow: Vec::new(),
         };
         chart.push(
             Temperature::confine(3.),
+            Temperature::confine(2.),
+            Temperature::confine(4.),
             Precipitation::confine(4.),
             Precipitation::confine(5.),
         );
         assert_eq!(chart.temperature.len(), 3);
+        assert!(chart.is_complete());
+    }
+
+    #[test]
+    fn push_into_empty() {
+        let mut chart = Chart::empty(3);
+        for celcius in [1., 2., 3.] {
+            assert!(!chart.is_complete());
+            chart.push(
+                Temperature::from_celcius(celcius),
+                Temperature::from_celcius(celcius - 4.),
+                Temperature::from_celcius(celcius + 4.),
+                Precipitation::confine(1.),
+                Precipitation::confine(0.5),
+            );
+        }
+        assert_eq!(chart.months(), 3);
+        assert!(chart.is_complete());
+
+        // placeholders without any months grow as well
+        let mut placeholder = Chart::empty(0);
+        placeholder.push(
+            Temperature::from_celcius(1.),
+            Temperature::from_celcius(0.),
+            Temperature::from_celcius(2.),
+            Precipitation::confine(1.),
+            Precipitation::confine(1.),
+        );
+        assert_eq!(placeholder.months(), 1);
+        assert!(placeholder.is_complete());
+    }
+
+    #[test]
+    fn push_onto_new() {
+        let mut chart = Chart::new(
+            vec![Temperature::from_celcius(1.); 2],
+            vec![Temperature::from_celcius(0.); 2],
+            vec![Temperature::from_celcius(2.); 2],
+            vec![Precipitation::confine(1.); 2],
+            vec![Precipitation::confine(1.); 2],
+        );
+        assert!(chart.is_complete());
+        chart.push(
+            Temperature::from_celcius(3.),
+            Temperature::from_celcius(2.),
+            Temperature::from_celcius(4.),
+            Precipitation::confine(4.),
+            Precipitation::confine(5.),
+        );
+        assert_eq!(chart.months(), 3);
+        assert_eq!(chart.rainfall().len(), 3);
+        assert!(chart.is_complete());
     }
 }
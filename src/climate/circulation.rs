@@ -1,91 +1,100 @@
 use crate::{
     carto::{
         brane::{Brane, Resolution},
-        datum::{DatumRe, DatumZa},
+        datum::DatumZa,
         flux::Flux,
-        honeycomb::{Hexagon, HoneyCellToroidal},
+        honeycomb::HoneyCellToroidal,
     },
+    climate::{
+        atmosphere::Atmosphere, cryosphere::Cryosphere, moisture::Advection, ocean::Currents,
+        orbit::Orbit,
+    },
+    geology::soil::Soils,
     units::{Elevation, Precipitation, Temperature, Unit},
 };
 use log::trace;
-use noise::{NoiseFn, OpenSimplex, Seedable};
 use petgraph::{graph::NodeIndex, visit::EdgeRef, Direction};
-use splines::{Interpolation, Key, Spline};
 use std::collections::VecDeque;
 
 use std::f64::consts::TAU;
-const SQRT3B2: f64 = 0.8660254;
-pub const OCNLV: f64 = 0.333333;
-
-/* # bedrock generation */
-
-pub fn bedrock(seed: u32) -> Brane<Elevation> {
-    // load base from file
-    let elevation_base = Brane::<Elevation>::load("elevation-base".to_string()).release();
-
-    // prepeare noise
-    let noise = OpenSimplex::new().set_seed(seed);
-    let amplicies = [
-        2f64.recip(),
-        4f64.recip(),
-        8f64.recip(),
-        12f64.recip(),
-        24f64.recip(),
-        48f64.recip(),
-        96f64.recip(),
-        192f64.recip(),
-    ]
-    .zip([3., 6., 12., 24., 48., 96., 192., 384.]);
-    let toroidal_sample = |datum: &DatumRe| -> f64 {
-        let fractional_brownian_motion = |x: f64, y: f64| {
-            amplicies
-                .map(|(amplitude, frequency)| {
-                    amplitude
-                        * noise.get([
-                            // toroidal wrapping
-                            frequency * x.cos(),
-                            frequency * x.sin(),
-                            frequency * SQRT3B2 * y.cos(), // undistort geometry on the hexagon
-                            frequency * SQRT3B2 * y.sin(), // undistort geometry on the hexagon
-                        ])
-                })
-                .iter()
-                .sum::<f64>()
-        };
+pub const OCNLV: f64 = 0.333333; // reference ocean level for generating bedrock
+pub const LAPSE_RATE: f64 = 1. / 162.; // fall in temperature for one meter
+
+/* # configuration */
+
+#[derive(Clone, Debug)]
+pub struct SimulationConfig {
+    /// number of months in a year
+    pub months: usize,
+    /// resolution of the coarse grid used for rainfall
+    pub resolution_small: Resolution,
+    /// number of samples over a year when averaging insolation
+    pub insolation_detail: usize,
+    /// how strongly opposing winds raise continentality
+    pub continentality_weight: f64,
+    pub atmosphere: Atmosphere,
+    pub advection: Advection,
+    pub currents: Currents,
+    pub cryosphere: Cryosphere,
+    pub soils: Soils,
+}
 
-        let x: f64 = TAU * datum.x;
-        let y: f64 = TAU * (datum.x + datum.y); // undistort geometry on the hexagon
-        fractional_brownian_motion(
-            x + fractional_brownian_motion(x, y),
-            y + fractional_brownian_motion(x, y),
-        )
-    };
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            months: 24,
+            resolution_small: Resolution::confine(216),
+            insolation_detail: 6usize.pow(3),
+            continentality_weight: 8.,
+            atmosphere: Atmosphere::default(),
+            advection: Advection::default(),
+            currents: Currents::default(),
+            cryosphere: Cryosphere::default(),
+            soils: Soils::default(),
+        }
+    }
+}
 
-    // prepare curve
-    let elevation_curve: Spline<f64, f64> = {
-        let step = 256f64.recip();
-        Spline::from_vec(vec![
-            Key::new(0., 0., Interpolation::Linear),
-            Key::new(OCNLV - 0.08, OCNLV - 12.0 * step, Interpolation::Linear),
-            Key::new(OCNLV, OCNLV, Interpolation::Linear),
-            Key::new(OCNLV + 0.04, OCNLV + 8.0 * step, Interpolation::Linear),
-            // Key::new(0.03, shelf + 4.0 * step, Interpolation::Linear),
-            // Key::new(0.27, shelf + 16.0 * step, Interpolation::Linear),
-            Key::new(1., 1., Interpolation::Linear),
-        ])
-    };
+/* # ocean */
 
-    elevation_base.operate_by_index(|jndex| {
-        let base = elevation_base.grid[jndex];
-        let noise = toroidal_sample(
-            &DatumZa::enravel(jndex, elevation_base.resolution).cast(elevation_base.resolution),
-        );
-        Elevation::confine(
-            elevation_curve
-                .clamped_sample(base + noise * (base - OCNLV).abs().powf(0.72).max(0.06))
-                .unwrap(),
-        )
-    })
+/// ocean level leaving a given fraction of the world above it
+pub fn ocean_level_for_land(elevation: &Brane<Elevation>, land_fraction: f64) -> Elevation {
+    elevation.quantile((1. - land_fraction).clamp(0., 1.))
+}
+
+/// fraction of the world above a given ocean level
+pub fn land_fraction(elevation: &Brane<Elevation>, ocean: Elevation) -> f64 {
+    elevation
+        .grid
+        .iter()
+        .filter(|value| **value >= ocean)
+        .count() as f64
+        / elevation.resolution.square() as f64
+}
+
+/// number of hexagon edges separating land from ocean
+pub fn coastline(elevation: &Brane<Elevation>, ocean: Elevation) -> usize {
+    let resolution = elevation.resolution;
+    (0..resolution.square())
+        .filter(|jndex| elevation.grid[*jndex] < ocean)
+        .map(|jndex| {
+            DatumZa::enravel(jndex, resolution)
+                .ambit_toroidal(resolution.into())
+                .iter()
+                .filter(|neighbour| elevation.grid[neighbour.unravel(resolution)] >= ocean)
+                .count()
+        })
+        .sum()
+}
+
+/// ocean level giving a coastline closest to a given number of hexagon edges
+pub fn ocean_level_for_coastline(elevation: &Brane<Elevation>, length: usize) -> Elevation {
+    // coastline length is not monotonic in the ocean level, so sample quantiles
+    let detail = 96;
+    (1..detail)
+        .map(|q| elevation.quantile(q as f64 / detail as f64))
+        .min_by_key(|ocean| (coastline(elevation, *ocean) as i64 - length as i64).abs())
+        .expect("there is at least one quantile")
 }
 
 pub fn ocean_tiles(elevation: &Brane<Elevation>, ocean: Elevation) -> Brane<bool> {
@@ -103,59 +112,18 @@ pub fn altitude_above_ocean_level(
 
 /* # insolation */
 
-fn insolation_at_datum(datum: DatumRe, solar_time: f64) -> f64 {
-    let solar_ellipse = |time: f64| -> DatumRe {
-        let eccentricity: f64 = 0.42;
-        let radius_major: f64 = 0.36;
-        let angle: f64 = -TAU * 3f64.recip();
-
-        let linear = eccentricity * radius_major;
-        let radius_minor = (radius_major.powi(2) - linear.powi(2)).sqrt();
-
-        // velocities come from the vis viva equation at abfocal and peryfocal points
-        let velo_max: f64 =
-            ((radius_major + linear) * (radius_major - linear).recip() * radius_major.recip())
-                .sqrt();
-        let velo_min: f64 =
-            ((radius_major - linear) * (radius_major + linear).recip() * radius_major.recip())
-                .sqrt();
-
-        // should approximate the changening speed of the orbiting body
-        //    due to the constant areal velocity
-        let position = TAU
-            * (velo_max * time - (7. * velo_max + 8. * velo_min - 15.) * time.powi(2)
-                + (18. * velo_max + 32. * velo_min - 50.) * time.powi(3)
-                - (20. * velo_max + 40. * velo_min - 60.) * time.powi(4)
-                + (8. * velo_max + 16. * velo_min - 24.) * time.powi(5));
-
-        let focus = DatumRe::new(linear * angle.cos(), linear * angle.sin());
-        let ellipse = focus
-            - DatumRe::new(
-                radius_major * angle.cos() * position.cos(),
-                radius_major * angle.sin() * position.cos(),
-            )
-            + DatumRe::new(
-                radius_minor * -angle.sin() * position.sin(),
-                radius_minor * angle.cos() * position.sin(),
-            );
-        ellipse.uncentre()
-    };
-
-    // encodes the relationship between the ground distance between points
-    //    and the received insolation
-    let insolation_curve = |distance: f64| -> f64 { (TAU * 4f64.recip() * distance).cos() };
-
-    insolation_curve(datum.distance(&solar_ellipse(solar_time)))
-}
-
-pub fn temperature_average(resolution: Resolution) -> Brane<Temperature> {
+pub fn temperature_average(
+    resolution: Resolution,
+    orbit: &Orbit,
+    config: &SimulationConfig,
+) -> Brane<Temperature> {
     trace!("calculating average insolation");
 
-    let detail = 6usize.pow(3);
+    let detail = config.insolation_detail;
     Brane::<Temperature>::create_by_datum(resolution, |datum| {
         Temperature::confine(
             (0..detail)
-                .map(|time| insolation_at_datum(datum, time as f64 / detail as f64))
+                .map(|time| orbit.insolation(datum, time as f64 / detail as f64))
                 .sum::<f64>()
                 / detail as f64,
         )
@@ -164,6 +132,7 @@ pub fn temperature_average(resolution: Resolution) -> Brane<Temperature> {
 
 pub fn temperature_at_ocean_level(
     solar_time: f64,
+    orbit: &Orbit,
     temperature_average: &Brane<Temperature>,
     continentality: &Brane<f64>,
 ) -> Brane<Temperature> {
@@ -174,7 +143,7 @@ pub fn temperature_at_ocean_level(
     match temperature_average.resolution == continentality.resolution {
         true => continentality.operate_by_index(|j| {
             temperature_value(
-                insolation_at_datum(
+                orbit.insolation(
                     DatumZa::enravel(j, continentality.resolution).cast(continentality.resolution),
                     solar_time,
                 ),
@@ -190,8 +159,7 @@ pub fn temperature_at_altitude(
     temperature_at_ocean: &Brane<Temperature>,
     altitude_above_ocean: &Brane<Elevation>,
 ) -> Brane<Temperature> {
-    let lapse_rate = 162f64.recip(); // fall in temperature for one meter
-    let lapse_value = |altitude: Elevation| -> f64 { altitude.meters() as f64 * lapse_rate };
+    let lapse_value = |altitude: Elevation| -> f64 { altitude.meters() as f64 * LAPSE_RATE };
     temperature_at_ocean.operate_by_index(|j| {
         Temperature::from_celcius(
             temperature_at_ocean.grid[j].celcius() - lapse_value(altitude_above_ocean.grid[j]),
@@ -199,6 +167,31 @@ pub fn temperature_at_altitude(
     })
 }
 
+/* # diurnal cycle */
+
+/// fraction of the day spent in daylight at every datum
+pub fn day_length(resolution: Resolution, solar_time: f64, orbit: &Orbit) -> Brane<f64> {
+    Brane::create_by_datum(resolution, |datum| orbit.day_length(datum, solar_time))
+}
+
+/// difference between the daily maximum and minimum temperature, in degrees celcius
+pub fn diurnal_range(
+    day_length: &Brane<f64>,
+    continentality: &Brane<f64>,
+    altitude_above_ocean: &Brane<Elevation>,
+    orbit: &Orbit,
+) -> Brane<f64> {
+    day_length.operate_by_index(|j| {
+        // the ocean barely warms over a day while inland air swings widely
+        let exposure = 2. + 16. * continentality.grid[j].clamp(0., 1.);
+        // thin air at altitude holds less heat through the night
+        let thinness = 1. + altitude_above_ocean.grid[j].meters().max(0) as f64 / 10000.;
+        // endless days or nights leave little room for a cycle
+        let daylight = 4. * day_length.grid[j] * (1. - day_length.grid[j]);
+        exposure * thinness * daylight * orbit.day.sqrt()
+    })
+}
+
 /* # continentality */
 
 fn wind(
@@ -223,6 +216,7 @@ pub fn continentality(
     altitude_above_ocean: &Brane<Elevation>,
     temperature: &Brane<Temperature>,
     ocean_tiles: &Brane<bool>,
+    config: &SimulationConfig,
 ) -> Brane<f64> {
     trace!("calculating continentality");
     let resolution = altitude_above_ocean.resolution;
@@ -258,12 +252,14 @@ pub fn continentality(
         for neighbour in here.ambit_toroidal(resolution.into()) {
             let jndex = neighbour.unravel(resolution);
             let total_cowind = continentality.grid[here_jndex]
-                + 8. * cowind(
-                    altitude_above_ocean.grid[here_jndex],
-                    temperature.grid[here_jndex],
-                    altitude_above_ocean.grid[jndex],
-                    temperature.grid[jndex],
-                ) / f64::from(resolution);
+                + config.continentality_weight
+                    * cowind(
+                        altitude_above_ocean.grid[here_jndex],
+                        temperature.grid[here_jndex],
+                        altitude_above_ocean.grid[jndex],
+                        temperature.grid[jndex],
+                    )
+                    / f64::from(resolution);
             if continentality.grid[jndex] > total_cowind {
                 continentality.grid[jndex] = total_cowind;
                 queue.push_back(neighbour);
@@ -284,116 +280,6 @@ pub fn evapotranspiration_potential(temperature: &Brane<Temperature>) -> Brane<P
     })
 }
 
-/* # rainfall */
-
-#[derive(Clone, Copy)]
-pub struct Counted<T> {
-    value: T,
-    counter: usize,
-}
-
-impl<T> Counted<T> {
-    pub fn new(value: T) -> Self {
-        Self { value, counter: 2 }
-    }
-
-    pub fn unwrap(self) -> T {
-        self.value
-    }
-
-    pub fn is_nonzero(&self) -> bool {
-        self.counter > 0
-    }
-
-    pub fn update(&mut self, value: T) {
-        if self.is_nonzero() {
-            self.value = value;
-            self.counter -= 1;
-        }
-    }
-}
-
-pub fn rainfall(
-    altitude_above_ocean: &Brane<Elevation>,
-    temperature: &Brane<Temperature>,
-    evaporation_potential: &Brane<Precipitation>,
-    continentality: &Brane<f64>,
-    ocean_tiles: &Brane<bool>,
-) -> Brane<Precipitation> {
-    let resolution = altitude_above_ocean.resolution;
-
-    // create storage and prepopulate
-    let mut queue = VecDeque::new();
-    let mut precipitation = evaporation_potential.operate_by_index(|jndex| {
-        Counted::new(evaporation_potential.grid[jndex] * (1. - continentality.grid[jndex]))
-    });
-
-    // preopulate oceans with evaporation
-    for jndex in 0..resolution.square() {
-        if ocean_tiles.grid[jndex] {
-            queue.push_back(DatumZa::enravel(jndex, resolution));
-        }
-    }
-
-    while !queue.is_empty() {
-        for target in queue
-            .pop_front()
-            .expect("queue was not empty")
-            .ambit_toroidal(resolution.into())
-        {
-            let jndex_target = target.unravel(resolution);
-            let rain = target
-                .ball_toroidal(1, resolution.into())
-                .iter()
-                .map(|source| {
-                    let jndex_source = source.unravel(resolution);
-                    std::cmp::min_by(
-                        evaporation_potential.grid[jndex_source],
-                        precipitation.grid[jndex_source].unwrap(),
-                        |a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Less), // in case of error return precipitation
-                    ) * wind(
-                        altitude_above_ocean.grid[jndex_source],
-                        temperature.grid[jndex_source],
-                        altitude_above_ocean.grid[jndex_target],
-                        temperature.grid[jndex_target],
-                    ) * (1.
-                        + source
-                            .ambit_toroidal(resolution.into())
-                            .iter()
-                            .map(|neighbour| {
-                                let jndex_neighbour = neighbour.unravel(resolution);
-                                wind(
-                                    altitude_above_ocean.grid[jndex_source],
-                                    temperature.grid[jndex_source],
-                                    altitude_above_ocean.grid[jndex_neighbour],
-                                    temperature.grid[jndex_neighbour],
-                                )
-                            })
-                            .sum::<f64>())
-                    .recip()
-                })
-                .reduce(|a, b| a + b)
-                .unwrap_or_else(|| Precipitation::confine(0.));
-            let old_rain = precipitation
-                .grid
-                .get_mut(jndex_target)
-                .expect("index from brane");
-            if old_rain.is_nonzero() {
-                if old_rain.unwrap() < rain {
-                    old_rain.update(rain);
-                    queue.push_back(target);
-                } else {
-                    old_rain.update(rain * 0.7 + old_rain.unwrap() * 0.3);
-                }
-            }
-        }
-    }
-
-    let adjustment_curve = |x: Precipitation| Precipitation::confine(2f64.powf(x.release()) - 1.);
-
-    precipitation.operate_by_index(|jndex| adjustment_curve(precipitation.grid[jndex].unwrap()))
-}
-
 /* # watershed */
 
 /// calculate the amount of water flowing down to every datum
@@ -433,19 +319,23 @@ mod test {
 
     #[test]
     fn temperature_at_ocean_level_match() {
-        let avg = temperature_average(RES);
+        let orbit = Orbit::default();
+        let avg = temperature_average(RES, &orbit, &SimulationConfig::default());
         let brane_zero = temperature_at_ocean_level(
             0.,
+            &orbit,
             &avg,
             &Brane::<f64>::create_by_index(RES, |j| (j % 2) as f64),
         );
         let brane_half = temperature_at_ocean_level(
             0.5,
+            &orbit,
             &avg,
             &Brane::<f64>::create_by_index(RES, |j| (j % 2) as f64),
         );
         let brane_one = temperature_at_ocean_level(
             1.,
+            &orbit,
             &avg,
             &Brane::<f64>::create_by_index(RES, |j| (j % 2) as f64),
         );
@@ -488,4 +378,21 @@ mod test {
             abs <= EPSILON
         );
     }
+
+    #[test]
+    fn ocean_level_solvers() {
+        let elevation =
+            Brane::create_by_index(RES, |j| Elevation::confine(j as f64 / RES.square() as f64));
+        let ocean = ocean_level_for_land(&elevation, 0.25);
+        assert_float_eq!(land_fraction(&elevation, ocean), 0.25, abs <= EPSILON);
+        assert_eq!(coastline(&elevation, Elevation::confine(0.)), 0);
+        assert_eq!(coastline(&elevation, Elevation::confine(2.)), 0);
+
+        let length = coastline(&elevation, ocean);
+        assert!(length > 0);
+        assert_eq!(
+            coastline(&elevation, ocean_level_for_coastline(&elevation, length)),
+            length
+        );
+    }
 }
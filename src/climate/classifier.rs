@@ -0,0 +1,15 @@
+use crate::{carto::colour::Ink, climate::chart::Chart};
+
+/// a scheme sorting climate charts into classes
+pub trait Classifier: Sync {
+    type Class: Copy + Send + Sync;
+    type Palette: Ink<Option<Self::Class>>;
+
+    fn classify(&self, chart: &Chart) -> Self::Class;
+
+    /// class of land buried under a glacier
+    fn glacial(&self) -> Self::Class;
+
+    /// ink for maps of the classes, with the ocean as none
+    fn palette(&self) -> Self::Palette;
+}
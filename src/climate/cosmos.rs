@@ -1,152 +1,185 @@
-#[allow(unused_imports)]
-use crate::carto::{colour as clr, render::Renderable};
 use crate::{
-    carto::brane::{Brane, Resolution},
+    carto::{
+        brane::{Brane, Resolution},
+        flux::Flux,
+        render::Renderable,
+    },
     climate::{
-        chart::{Chart, Zone},
+        chart::{Chart, Index, WaterBalance},
         circulation::{
-            altitude_above_ocean_level, continentality, evapotranspiration_potential, ocean_tiles,
-            rainfall, temperature_at_altitude, temperature_at_ocean_level, temperature_average,
-            OCNLV,
+            altitude_above_ocean_level, continentality, ocean_level_for_land, ocean_tiles, shed,
+            temperature_average, SimulationConfig, OCNLV,
         },
-        vegetation::{Vege, ZoneType},
+        classifier::Classifier,
+        cryosphere::freeze,
+        glacier::{glaciate, Glaciation},
+        hydrograph::{hydrographs, Hydrographs, Routing},
+        koppen::{Koppen, KoppenGeiger},
+        month::simulate_month,
+        orbit::Orbit,
+        vegetation::{Vege, Vegetation},
+    },
+    geology::{
+        bedrock::{bedrock_from, BedrockConfig},
+        continents::{continent_count, continents, Continents},
+        soil::soil_capacity,
     },
-    regression::predict_brane,
     units::{Elevation, Precipitation, Temperature, Unit},
 };
 use log::trace;
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::Path};
 
-/* # months */
-
-pub struct Month {
-    pub temp: Brane<Temperature>,
-    pub rain: Brane<Precipitation>,
-    pub pevt: Brane<Precipitation>,
-}
+pub use crate::climate::month::Month;
 
-impl Month {
-    pub fn new(
-        temp: Brane<Temperature>,
-        rain: Brane<Precipitation>,
-        pevt: Brane<Precipitation>,
-    ) -> Self {
-        Self { temp, rain, pevt }
-    }
-
-    pub fn upscale(
-        self,
-        altitude_smol: &Brane<Elevation>,
-        continentality_smol: &Brane<f64>,
-        altitude: &Brane<Elevation>,
-        continentality: &Brane<f64>,
-    ) -> Self {
-        let temperature = predict_brane(
-            &self.temp.release(),
-            altitude_smol,
-            continentality_smol,
-            altitude,
-            continentality,
-        )
-        .operate_by_value(Temperature::confine);
-        Self::new(
-            temperature.clone(),
-            self.rain.upscale(altitude.resolution),
-            evapotranspiration_potential(&temperature),
-        )
-    }
+/* # cosmos */
 
-    pub fn render(&self) {
-        self.temp.stats();
-        self.temp
-            .upscale(Resolution::confine(324))
-            .render("temp".to_string(), clr::CelciusInk);
-        self.rain.stats();
-        self.rain
-            .upscale(Resolution::confine(324))
-            .render("rain".to_string(), clr::MoonMeterInk);
-        self.pevt.stats();
-        self.pevt
-            .upscale(Resolution::confine(324))
-            .render("pevt".to_string(), clr::MoonMeterInk);
-    }
+#[derive(Default)]
+pub struct Genesis {
+    pub continents: Continents,
+    pub bedrock: BedrockConfig,
 }
 
-fn simulate_month(
-    solar_time: f64,
-    altitude: &Brane<Elevation>,
-    continentality: &Brane<f64>,
-    temperature_average: &Brane<Temperature>,
-    ocean_tiles: &Brane<bool>,
-) -> Month {
-    let temperature = temperature_at_altitude(
-        &temperature_at_ocean_level(solar_time, temperature_average, continentality),
-        altitude,
-    );
-    let evaporation_potential = evapotranspiration_potential(&temperature);
-    Month::new(
-        temperature.clone(),
-        rainfall(
-            altitude,
-            &temperature,
-            &evaporation_potential,
-            continentality,
-            ocean_tiles,
-        ),
-        evaporation_potential,
-    )
+/// what a saved world keeps beside its bedrock
+#[derive(Deserialize, Serialize)]
+struct Shore {
+    ocean_level: f64,
 }
 
-/* # cosmos */
+impl Shore {
+    /// ocean level from the contents of its file, if there is one
+    fn read(text: io::Result<String>) -> io::Result<f64> {
+        match text {
+            Ok(text) => toml::from_str::<Shore>(&text)
+                .map(|shore| shore.ocean_level)
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(OCNLV),
+            Err(error) => Err(error),
+        }
+    }
+}
 
 pub struct Cosmos {
     pub altitude: Brane<Elevation>,
+    pub ocean_level: Elevation,
     pub charts: Brane<Chart>,
+    /// thickness of the ice lying on the bedrock
+    pub ice: Brane<Elevation>,
 }
 
 impl Cosmos {
     /// initialise cosmos with given bedrock brane
-    pub fn new(altitude: Brane<Elevation>, charts: Brane<Chart>) -> Self {
-        Self { altitude, charts }
+    pub fn new(altitude: Brane<Elevation>, ocean_level: Elevation, charts: Brane<Chart>) -> Self {
+        let resolution = altitude.resolution;
+        Self {
+            altitude,
+            ocean_level,
+            charts,
+            ice: Brane::create_by_index(resolution, |_| Elevation::confine(0.)),
+        }
+    }
+
+    /// elevation of the bedrock with its ice on top
+    pub fn surface(&self) -> Brane<Elevation> {
+        self.altitude
+            .operate_by_index(|j| self.altitude.grid[j] + self.ice.grid[j])
+    }
+
+    /// save the bedrock together with its ocean level
+    pub fn save(&self) {
+        self.altitude.save("elevation".to_string());
+        let path_name = "static/ocean-level.toml";
+        trace!("saving ocean level to {}", path_name);
+        fs::write(
+            Path::new(path_name),
+            toml::to_string(&Shore {
+                ocean_level: self.ocean_level.release(),
+            })
+            .expect("ocean level should serialise"),
+        )
+        .unwrap();
     }
 
-    pub fn load() -> Self {
+    /// load the bedrock and the ocean level it was saved with
+    ///     worlds saved before the ocean level was stored keep the old fixed one
+    pub fn load() -> io::Result<Self> {
         let elevation = Brane::<Elevation>::load("elevation".to_string());
         let resolution = elevation.resolution;
+        let path_name = "static/ocean-level.toml";
+        trace!("loading ocean level from {}", path_name);
+        let ocean_level = Shore::read(fs::read_to_string(Path::new(path_name)))?;
+        Ok(Self::new(
+            elevation,
+            Elevation::confine(ocean_level),
+            Brane::create_by_index(resolution, |_| Chart::empty(0)),
+        ))
+    }
+
+    /// generate a world from nothing but a seed
+    ///     the ocean level is solved for the land fraction once the bedrock noise is in,
+    ///     and seeds following the first are tried until that noise leaves
+    ///     as many continents as asked for, keeping the closest world otherwise
+    pub fn generate(seed: u32, resolution: Resolution, genesis: &Genesis) -> Self {
+        let params = &genesis.continents;
+        let mut best: Option<(usize, Brane<Elevation>, Elevation)> = None;
+        for attempt in 0..params.attempts.max(1) {
+            let seed = seed.wrapping_add(attempt as u32);
+            let elevation = bedrock_from(
+                &continents(resolution, seed, params),
+                seed,
+                &genesis.bedrock,
+            );
+            let ocean_level = ocean_level_for_land(&elevation, params.land_fraction);
+            let miss = continent_count(&elevation, ocean_level, params).abs_diff(params.count);
+            trace!("world from seed {} misses the continents by {}", seed, miss);
+            if best.as_ref().map_or(true, |(least, _, _)| miss < *least) {
+                best = Some((miss, elevation, ocean_level));
+            }
+            if miss == 0 {
+                break;
+            }
+        }
+        let (_, elevation, ocean_level) = best.expect("at least one world was generated");
         Self::new(
             elevation,
-            Brane::create_by_index(resolution, |_| Chart::new(vec![], vec![], vec![])),
+            ocean_level,
+            Brane::create_by_index(resolution, |_| Chart::empty(0)),
         )
     }
 
-    pub fn simulate(self) -> Self {
-        let elevation = self.altitude;
+    /// simulate the climate for a world orbiting its star
+    pub fn simulate(self, orbit: &Orbit, config: &SimulationConfig) -> Self {
+        let elevation = self.surface();
         let resolution = elevation.resolution;
-        let ocean_lv = Elevation::confine(OCNLV);
+        let ocean_lv = self.ocean_level;
 
         // # establish small branes
-        let resolution_small = Resolution::confine(216); // mostly for rainfall
+        let resolution_small = config.resolution_small; // mostly for rainfall
         let elevation_small = elevation.upscale(resolution_small);
         let ocean_tiles_small = ocean_tiles(&elevation_small, ocean_lv);
         let altitude_small = altitude_above_ocean_level(&elevation_small, ocean_lv);
-        let temperature_average_small = temperature_average(resolution_small);
+        let temperature_average_small = temperature_average(resolution_small, orbit, config);
         let continentality_small = continentality(
             &altitude_small,
             &temperature_average_small,
             &ocean_tiles_small,
+            config,
         );
         // continentality_small.stats_raw();
         // continentality_small.render("cont".to_string(), clr::HueInk::new(0.08, 0.92));
 
         // # small run
         trace!("simulating atmospheric condidtions");
-        let year_len = 24;
+        let year_len = config.months;
         let year_small = (0..year_len).map(|sol| {
             simulate_month(
                 sol as f64 / year_len as f64,
+                orbit,
                 &altitude_small,
                 &continentality_small,
                 &temperature_average_small,
                 &ocean_tiles_small,
+                config,
             )
         });
 
@@ -156,6 +189,7 @@ impl Cosmos {
         let year = year_small
             .map(|month| {
                 month.upscale(
+                    orbit,
                     &altitude_small,
                     &continentality_small,
                     &altitude,
@@ -163,37 +197,124 @@ impl Cosmos {
                 )
             })
             .collect::<Vec<Month>>();
+        let year = freeze(year, &ocean_tiles(&elevation, ocean_lv), &config.cryosphere);
 
         //year[0].render();
 
-        Self::new(
-            elevation,
-            Brane::create_by_index(resolution, |j| {
-                Chart::new(
-                    year.iter()
-                        .map(|month| month.temp.grid[j])
-                        .collect::<Vec<Temperature>>(),
-                    year.iter()
-                        .map(|month| month.rain.grid[j])
-                        .collect::<Vec<Precipitation>>(),
-                    year.iter()
-                        .map(|month| month.pevt.grid[j])
-                        .collect::<Vec<Precipitation>>(),
-                )
-            }),
-        )
+        let capacity = soil_capacity(&elevation, ocean_lv, &config.soils);
+        let charts = Brane::create_by_index(resolution, |j| {
+            Chart::new(
+                year.iter()
+                    .map(|month| month.temp.grid[j])
+                    .collect::<Vec<Temperature>>(),
+                year.iter()
+                    .map(|month| month.temp_min.grid[j])
+                    .collect::<Vec<Temperature>>(),
+                year.iter()
+                    .map(|month| month.temp_max.grid[j])
+                    .collect::<Vec<Temperature>>(),
+                year.iter()
+                    .map(|month| month.rain.grid[j])
+                    .collect::<Vec<Precipitation>>(),
+                year.iter()
+                    .map(|month| month.pevt.grid[j])
+                    .collect::<Vec<Precipitation>>(),
+            )
+            .with_soil(capacity.grid[j])
+            .with_snow(
+                year.iter()
+                    .map(|month| month.snow.grid[j])
+                    .collect::<Vec<Precipitation>>(),
+            )
+        });
+        Self {
+            ice: self.ice,
+            ..Self::new(self.altitude, ocean_lv, charts)
+        }
     }
 
-    pub fn vege(&self) -> Brane<Option<Vege>> {
-        let ocean_tiles = self
-            .altitude
-            .operate_by_value_ref(|value| value.release() < OCNLV);
+    /// grow glaciers from the current charts and lay them over the bedrock
+    ///     the climate is simulated again over the raised surface
+    pub fn glaciate(self, params: &Glaciation, orbit: &Orbit, config: &SimulationConfig) -> Self {
+        let ice = glaciate(&self.altitude, self.ocean_level, &self.charts, params);
+        Self { ice, ..self }.simulate(orbit, config)
+    }
+
+    /// monthly soil water budget of every datum
+    pub fn water_balance(&self) -> Brane<WaterBalance> {
+        self.charts
+            .operate_by_value_ref(|chart| chart.water_balance())
+    }
+
+    /// water running off the full soils, gathered downhill over the year
+    pub fn runoff(&self) -> Brane<Precipitation> {
+        let surplus = self
+            .charts
+            .operate_by_value_ref(|chart| chart.water_balance().annual_surplus());
+        shed(&Flux::from(self.surface()), &surplus)
+    }
+
+    /// discharge of every datum through the year, with snow melting in spring
+    pub fn hydrographs(&self, params: &Routing) -> Hydrographs {
+        let balance = self
+            .charts
+            .operate_by_value_ref(|chart| chart.water_balance_with_snow());
+        let months = self.charts.grid.first().map_or(0, |chart| chart.months());
+        let runoff = (0..months)
+            .map(|month| balance.operate_by_value_ref(|water| water.surplus[month]))
+            .collect::<Vec<Brane<Precipitation>>>();
+        hydrographs(&Flux::from(self.surface()), &runoff, params)
+    }
+
+    /// any climate index of every datum
+    pub fn index(&self, index: Index) -> Brane<f64> {
+        self.charts.operate_by_value_ref(|chart| chart.index(index))
+    }
+
+    /// classes of any scheme, none over the ocean
+    pub fn classify<C: Classifier>(&self, classifier: &C) -> Brane<Option<C::Class>> {
+        let ocean_tiles = ocean_tiles(&self.altitude, self.ocean_level);
         self.charts.operate_by_index(|j| {
             if ocean_tiles.grid[j] {
                 None
+            } else if self.ice.grid[j] > Elevation::confine(0.) {
+                Some(classifier.glacial())
             } else {
-                Some(Vege::from(ZoneType::from(Zone::from(&self.charts.grid[j]))))
+                Some(classifier.classify(&self.charts.grid[j]))
             }
         })
     }
+
+    /// render the classes of any scheme in its own palette
+    pub fn render_classes<C: Classifier>(&self, classifier: &C, variable: String) {
+        self.classify(classifier)
+            .render(variable, classifier.palette());
+    }
+
+    pub fn vege(&self) -> Brane<Option<Vege>> {
+        self.classify(&Vegetation)
+    }
+
+    /// köppen-geiger classes, as an alternative to vege
+    pub fn koppen(&self) -> Brane<Option<Koppen>> {
+        self.classify(&KoppenGeiger)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ocean_level_from_file() {
+        assert_eq!(
+            Shore::read(Ok("ocean_level = 0.25".to_string())).unwrap(),
+            0.25
+        );
+        // worlds saved without the file keep the old level
+        let missing = io::Error::new(io::ErrorKind::NotFound, "no file");
+        assert_eq!(Shore::read(Err(missing)).unwrap(), OCNLV);
+        let malformed = Shore::read(Ok("ocean_level = ".to_string())).unwrap_err();
+        assert_eq!(malformed.kind(), io::ErrorKind::InvalidData);
+    }
 }
@@ -0,0 +1,159 @@
+use crate::{
+    carto::brane::Brane,
+    climate::month::Month,
+    units::{Precipitation, Unit},
+};
+use log::trace;
+
+#[derive(Clone, Debug)]
+pub struct Cryosphere {
+    /// temperature below which the sea freezes, in degrees celcius
+    pub freezing: f64,
+    /// snow melted for every degree celcius above zero in a month
+    pub melt: f64,
+    /// snow deep enough to cover the ground completely
+    pub saturation: f64,
+    /// cooling under a complete cover of snow or ice, in degrees celcius
+    pub albedo: f64,
+    /// largest number of years towards the equilibrium
+    pub years: usize,
+    /// change of cover taken as equilibrium
+    pub tolerance: f64,
+}
+
+impl Default for Cryosphere {
+    fn default() -> Self {
+        Self {
+            freezing: -1.8,
+            melt: 0.36,
+            saturation: 0.06,
+            albedo: 4.,
+            years: 12,
+            tolerance: 0.01,
+        }
+    }
+}
+
+/// share of precipitation falling as snow at a given temperature in degrees celcius
+pub fn snow_fraction(celcius: f64) -> f64 {
+    ((2. - celcius) / 4.).clamp(0., 1.)
+}
+
+/// let snow and sea ice come and go over the year, cooling where they lie
+///     repeats the year until the cover settles
+pub fn freeze(year: Vec<Month>, ocean_tiles: &Brane<bool>, params: &Cryosphere) -> Vec<Month> {
+    trace!("freezing snow and sea ice");
+    let resolution = ocean_tiles.resolution;
+    let zero = Brane::new(vec![0f64; resolution.square()], resolution);
+
+    let mut cover = vec![zero.clone(); year.len()];
+    let mut snow = zero;
+    let mut depths = Vec::new();
+    let mut ice = Vec::new();
+    for iteration in 0..params.years {
+        depths.clear();
+        ice.clear();
+        let mut covered = Vec::with_capacity(year.len());
+        for (month, cooling) in year.iter().zip(cover.iter()) {
+            let temp = month.temp.operate_by_index(|j| {
+                month.temp.grid[j].celcius() - params.albedo * cooling.grid[j]
+            });
+            let sea_ice =
+                temp.operate_by_index(|j| ocean_tiles.grid[j] && temp.grid[j] < params.freezing);
+            snow = snow.operate_by_index(|j| match ocean_tiles.grid[j] {
+                true => 0.,
+                false => {
+                    let fall = month.rain.grid[j].release() * snow_fraction(temp.grid[j]);
+                    let melt = params.melt * temp.grid[j].max(0.);
+                    (snow.grid[j] + fall - melt).max(0.)
+                }
+            });
+            covered.push(snow.operate_by_index(|j| match sea_ice.grid[j] {
+                true => 1.,
+                false => (snow.grid[j] / params.saturation).min(1.),
+            }));
+            depths.push(snow.clone());
+            ice.push(sea_ice);
+        }
+
+        let change = covered
+            .iter()
+            .zip(cover.iter())
+            .flat_map(|(a, b)| a.grid.iter().zip(b.grid.iter()).map(|(a, b)| (a - b).abs()))
+            .fold(0., f64::max);
+        cover = covered;
+        if change < params.tolerance {
+            trace!("cryosphere settled after {} years", iteration + 1);
+            break;
+        }
+    }
+
+    year.into_iter()
+        .zip(cover)
+        .zip(depths)
+        .zip(ice)
+        .map(|(((month, cover), depth), sea_ice)| {
+            month.cool(
+                &cover.operate_by_value(|c| c * params.albedo),
+                depth.operate_by_value(Precipitation::confine),
+                sea_ice,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{carto::brane::Resolution, units::Temperature};
+    use float_eq::assert_float_eq;
+    use std::f64::consts::TAU;
+    const RES: Resolution = Resolution::confine(6);
+
+    fn year(mean: f64) -> Vec<Month> {
+        (0..12)
+            .map(|m| {
+                // coldest in the first month, warmest in the seventh
+                let celcius = mean - 12. * (TAU * m as f64 / 12.).cos();
+                let temp = Brane::create_by_index(RES, |_| Temperature::from_celcius(celcius));
+                Month::new(
+                    temp.clone(),
+                    &Brane::create_by_index(RES, |_| 8.),
+                    Brane::create_by_index(RES, |_| Precipitation::confine(0.2)),
+                    Brane::create_by_index(RES, |_| Precipitation::confine(0.2)),
+                    Brane::create_by_index(RES, |_| 0.5),
+                    temp,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn snow_comes_and_goes() {
+        let ocean = Brane::create_by_index(RES, |j| j < 6);
+        let frozen = freeze(year(4.), &ocean, &Cryosphere::default());
+        let land = 12;
+        // deepest in late winter, gone by late summer
+        assert!(frozen[0].snow.grid[land].release() > 0.);
+        assert_eq!(frozen[6].snow.grid[land].release(), 0.);
+        assert!(frozen
+            .iter()
+            .all(|month| month.snow.grid[0].release() == 0.));
+        // ice cools the months it lies in
+        let plain = year(4.);
+        assert!(frozen[0].temp.grid[land] < plain[0].temp.grid[land]);
+        assert_float_eq!(
+            frozen[6].temp.grid[land].release(),
+            plain[6].temp.grid[land].release(),
+            abs <= 0.000001
+        );
+    }
+
+    #[test]
+    fn sea_freezes_in_winter() {
+        let ocean = Brane::create_by_index(RES, |_| true);
+        let frozen = freeze(year(4.), &ocean, &Cryosphere::default());
+        assert!(frozen[0].sea_ice.grid[0]);
+        assert!(!frozen[6].sea_ice.grid[0]);
+    }
+}
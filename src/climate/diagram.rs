@@ -0,0 +1,370 @@
+use crate::{
+    carto::datum::{DatumZa, Locate},
+    climate::{
+        chart::Chart, classifier::Classifier, cosmos::Cosmos, koppen::KoppenGeiger,
+        vegetation::Vegetation,
+    },
+    units::Unit,
+};
+use log::trace;
+use std::fs;
+use svg::{
+    node::element::{Line, Polygon, Polyline, Rectangle, Text},
+    Document,
+};
+
+/* # walter-lieth diagrams */
+
+// ten degrees celcius on the left axis match twenty milimeters on the right
+//    and above a hundred milimeters the right axis is compressed tenfold
+const MONTH_WIDTH: f64 = 24.;
+const DEGREE_HEIGHT: f64 = 2.4;
+const MARGIN: f64 = 48.;
+const HEADER: f64 = 48.;
+
+/// mean daily minimum in degrees celcius below which some nights probably freeze
+///     the coldest nights of a month fall a few degrees below the mean of its minima
+const PROBABLE_FROST: f64 = 4.;
+
+/// monthly precipitation as a height on the temperature axis
+fn precipitation_to_degrees(milimeters: f64) -> f64 {
+    if milimeters <= 100. {
+        milimeters / 2.
+    } else {
+        50. + (milimeters - 100.) / 20.
+    }
+}
+
+// horizontal ends, both lines and whether the first line is above
+type Piece = ([f64; 2], [f64; 2], [f64; 2], bool);
+
+/// pieces of the band between two lines over one month, split where they cross
+fn band(x: [f64; 2], a: [f64; 2], b: [f64; 2]) -> Vec<Piece> {
+    let (d0, d1) = (a[0] - b[0], a[1] - b[1]);
+    if d0 * d1 >= 0. {
+        vec![(x, a, b, d0 + d1 >= 0.)]
+    } else {
+        let s = d0 / (d0 - d1);
+        let cross = |line: [f64; 2]| line[0] + s * (line[1] - line[0]);
+        let xs = x[0] + s * (x[1] - x[0]);
+        vec![
+            ([x[0], xs], [a[0], cross(a)], [b[0], cross(b)], d0 > 0.),
+            ([xs, x[1]], [cross(a), a[1]], [cross(b), b[1]], d1 > 0.),
+        ]
+    }
+}
+
+fn label(x: f64, y: f64, anchor: &str, content: String) -> Text {
+    Text::new()
+        .set("x", x)
+        .set("y", y)
+        .set("text-anchor", anchor)
+        .set("font-family", "sans-serif")
+        .set("font-size", 9)
+        .add(svg::node::Text::new(content))
+}
+
+/// draw the climate diagram of walter and lieth for a single datum
+///     temperature in red and precipitation in blue, with the arid season yellow,
+///     the humid season light blue and months above a hundred milimeters dark blue
+///     frost is marked under every month, dark where the mean minimum freezes
+pub fn walter_lieth<L: Locate>(cosmos: &Cosmos, location: L, variable: String) -> Document {
+    let resolution = cosmos.charts.resolution;
+    let datum = location.locate(resolution);
+    let jndex = datum.unravel(resolution);
+    trace!("drawing climate diagram {} at {:?}", variable, datum);
+    let chart: &Chart = &cosmos.charts.grid[jndex];
+    let months = chart.months();
+
+    let temperature = chart
+        .temperature()
+        .iter()
+        .map(|t| t.celcius())
+        .collect::<Vec<f64>>();
+    let precipitation = chart
+        .rainfall()
+        .iter()
+        .map(|r| precipitation_to_degrees(r.release() * 324.))
+        .collect::<Vec<f64>>();
+
+    // the axis runs in tens of degrees and always shows the range from zero to fifty
+    let top = (temperature
+        .iter()
+        .chain(precipitation.iter())
+        .fold(50f64, |a, b| a.max(*b))
+        / 10.)
+        .ceil()
+        * 10.;
+    let bottom = (temperature.iter().fold(0f64, |a, b| a.min(*b)) / 10.).floor() * 10.;
+    let x = |month: usize| MARGIN + (month as f64 + 0.5) * MONTH_WIDTH;
+    let y = |degrees: f64| HEADER + (top - degrees) * DEGREE_HEIGHT;
+    let width = 2. * MARGIN + months as f64 * MONTH_WIDTH;
+    let height = y(bottom) + MARGIN;
+
+    let mut image = Document::new().set("viewBox", (0., 0., width, height)).add(
+        Rectangle::new()
+            .set("width", width)
+            .set("height", height)
+            .set("fill", "white"),
+    );
+
+    // seasons between the curves
+    let quad = |xs: [f64; 2], a: [f64; 2], b: [f64; 2], fill: &str| {
+        Polygon::new()
+            .set(
+                "points",
+                format!(
+                    "{},{} {},{} {},{} {},{}",
+                    xs[0],
+                    y(a[0]),
+                    xs[1],
+                    y(a[1]),
+                    xs[1],
+                    y(b[1]),
+                    xs[0],
+                    y(b[0])
+                ),
+            )
+            .set("fill", fill.to_string())
+    };
+    for month in 0..months.saturating_sub(1) {
+        let xs = [x(month), x(month + 1)];
+        let temperatures = [temperature[month], temperature[month + 1]];
+        let precipitations = [precipitation[month], precipitation[month + 1]];
+        let capped = precipitations.map(|p| p.min(50.));
+        for (xs, a, b, humid) in band(xs, capped, temperatures) {
+            image = image.add(quad(
+                xs,
+                a,
+                b,
+                if humid {
+                    "rgb(168, 204, 236)"
+                } else {
+                    "rgb(244, 214, 112)"
+                },
+            ));
+        }
+        for (xs, a, b, wet) in band(xs, precipitations, [50., 50.]) {
+            if wet {
+                image = image.add(quad(xs, a, b, "rgb(36, 72, 156)"));
+            }
+        }
+    }
+
+    // curves
+    let curve = |values: &[f64], stroke: &str| {
+        Polyline::new()
+            .set(
+                "points",
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(month, value)| format!("{},{}", x(month), y(*value)))
+                    .collect::<Vec<String>>()
+                    .join(" "),
+            )
+            .set("fill", "none")
+            .set("stroke", stroke.to_string())
+            .set("stroke-width", 1.5)
+    };
+    image = image
+        .add(curve(&precipitation, "rgb(36, 72, 156)"))
+        .add(curve(&temperature, "rgb(196, 52, 36)"));
+
+    // axes
+    let axis = |x0: f64, y0: f64, x1: f64, y1: f64| {
+        Line::new()
+            .set("x1", x0)
+            .set("y1", y0)
+            .set("x2", x1)
+            .set("y2", y1)
+            .set("stroke", "black")
+            .set("stroke-width", 0.5)
+    };
+    let (left, right) = (MARGIN, width - MARGIN);
+    image = image
+        .add(axis(left, y(top), left, y(bottom)))
+        .add(axis(right, y(top), right, y(bottom)))
+        .add(axis(left, y(0.), right, y(0.)));
+    let mut degrees = bottom;
+    while degrees <= top {
+        image = image.add(label(
+            left - 4.,
+            y(degrees) + 3.,
+            "end",
+            format!("{}", degrees),
+        ));
+        if degrees > 0. {
+            let milimeters = if degrees <= 50. {
+                2. * degrees
+            } else {
+                100. + 20. * (degrees - 50.)
+            };
+            image = image.add(label(
+                right + 4.,
+                y(degrees) + 3.,
+                "start",
+                format!("{}", milimeters),
+            ));
+        }
+        degrees += 10.;
+    }
+    image = image
+        .add(label(left, y(top) - 6., "middle", "°C".to_string()))
+        .add(label(right, y(top) - 6., "middle", "mm".to_string()));
+
+    // frost below the axis
+    for (month, minimum) in chart.temperature_min().iter().enumerate() {
+        let fill = match minimum.celcius() {
+            c if c < 0. => "rgb(36, 72, 156)",
+            c if c < PROBABLE_FROST => "rgb(168, 204, 236)",
+            _ => "none",
+        };
+        image = image
+            .add(
+                Rectangle::new()
+                    .set("x", x(month) - MONTH_WIDTH / 2.)
+                    .set("y", y(bottom) + 4.)
+                    .set("width", MONTH_WIDTH)
+                    .set("height", 8)
+                    .set("fill", fill)
+                    .set("stroke", "black")
+                    .set("stroke-width", 0.25),
+            )
+            .add(label(
+                x(month),
+                y(bottom) + 24.,
+                "middle",
+                format!("{}", month + 1),
+            ));
+    }
+
+    // header
+    let ocean = cosmos.altitude.grid[jndex] < cosmos.ocean_level;
+    let icy = cosmos.ice.grid[jndex].release() > 0.;
+    let (vege, koppen) = match (ocean, icy) {
+        (true, _) => ("ocean".to_string(), "ocean".to_string()),
+        (false, true) => (
+            format!("{:?}", Vegetation.glacial()),
+            KoppenGeiger.glacial().code(),
+        ),
+        (false, false) => (
+            format!("{:?}", Vegetation.classify(chart)),
+            KoppenGeiger.classify(chart).code(),
+        ),
+    };
+    let elevation = (cosmos.altitude.grid[jndex] + cosmos.ice.grid[jndex]).meters()
+        - cosmos.ocean_level.meters();
+    image = image
+        .add(label(
+            MARGIN,
+            16.,
+            "start",
+            format!(
+                "{} ({}, {}) {} m",
+                variable,
+                datum.x,
+                datum.y,
+                elevation.max(0)
+            ),
+        ))
+        .add(label(MARGIN, 28., "start", format!("{} {}", vege, koppen)))
+        .add(label(
+            right,
+            16.,
+            "end",
+            format!("{:.1} °C", chart.thermos().celcius()),
+        ))
+        .add(label(
+            right,
+            28.,
+            "end",
+            format!("{:.0} mm", chart.annual_rainfall().release() * 324.),
+        ));
+
+    image
+}
+
+/// save the climate diagram of a single datum to a .svg file
+pub fn save_walter_lieth<L: Locate>(cosmos: &Cosmos, location: L, variable: String) {
+    let datum = location.locate(cosmos.charts.resolution);
+    let path_name = format!("bounce/{}-{}-{}.svg", variable, datum.x, datum.y);
+    let image = walter_lieth(cosmos, datum, variable);
+    fs::create_dir_all("bounce").unwrap();
+    svg::save(&path_name, &image).unwrap();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        carto::brane::{Brane, Resolution},
+        units::{Elevation, Precipitation, Temperature},
+    };
+    use float_eq::assert_float_eq;
+    use svg::parser::Event;
+    const RES: Resolution = Resolution::confine(6);
+
+    #[test]
+    fn band_splits_where_lines_cross() {
+        let pieces = band([0., 2.], [0., 2.], [1., 1.]);
+        assert_eq!(pieces.len(), 2);
+        assert!(!pieces[0].3);
+        assert!(pieces[1].3);
+        assert_float_eq!(pieces[0].0[1], 1., abs <= 0.000001);
+        assert_float_eq!(pieces[1].1[0], 1., abs <= 0.000001);
+        assert_eq!(band([0., 2.], [2., 3.], [1., 1.]).len(), 1);
+    }
+
+    #[test]
+    fn precipitation_axis_is_compressed() {
+        assert_float_eq!(precipitation_to_degrees(60.), 30., abs <= 0.000001);
+        assert_float_eq!(precipitation_to_degrees(300.), 60., abs <= 0.000001);
+    }
+
+    #[test]
+    fn walter_lieth_draws_svg() {
+        let celcius = [-4., -2., 3., 8., 13., 17., 19., 18., 14., 9., 4., -1.];
+        let series = |shift: f64| {
+            celcius
+                .iter()
+                .map(|c| Temperature::from_celcius(c + shift))
+                .collect::<Vec<Temperature>>()
+        };
+        let cosmos = Cosmos::new(
+            Brane::create_by_index(RES, |_| Elevation::confine(0.5)),
+            Elevation::confine(0.25),
+            Brane::create_by_index(RES, |_| {
+                Chart::new(
+                    series(0.),
+                    series(-5.),
+                    series(5.),
+                    [60., 50., 40., 30., 80., 120., 140., 90., 40., 20., 30., 50.]
+                        .into_iter()
+                        .map(|mm| Precipitation::confine(mm / 324.))
+                        .collect(),
+                    vec![Precipitation::confine(0.1); 12],
+                )
+            }),
+        );
+        let image = walter_lieth(&cosmos, DatumZa::new(2, 3), "test-diagram".to_string());
+
+        let content = image.to_string();
+        let mut tags = Vec::new();
+        let mut texts = Vec::new();
+        for event in svg::read(&content).unwrap() {
+            match event {
+                Event::Tag(tag, _, _) => tags.push(tag.to_string()),
+                Event::Text(text) => texts.push(text.to_string()),
+                Event::Error(error) => panic!("malformed diagram: {}", error),
+                _ => {}
+            }
+        }
+        assert_eq!(tags.first().map(String::as_str), Some("svg"));
+        assert_eq!(tags.iter().filter(|tag| *tag == "polyline").count(), 2);
+        assert!(tags.iter().filter(|tag| *tag == "rect").count() > 12);
+        assert!(texts
+            .iter()
+            .any(|text| text == "test-diagram (2, 3) 3456 m"));
+    }
+}
@@ -0,0 +1,167 @@
+use crate::{
+    carto::{brane::Brane, datum::DatumZa, honeycomb::HoneyCellToroidal},
+    climate::{
+        chart::Chart,
+        circulation::{ocean_tiles, LAPSE_RATE},
+    },
+    units::{Elevation, Unit},
+};
+use log::trace;
+
+// elevation of ice formed from one unit of precipitation
+//    milimeters of water, less dense as ice, over meters of elevation
+const ICE_PER_PRECIPITATION: f64 = 324. / 1000. / 0.917 / 13824.;
+
+#[derive(Clone, Debug)]
+pub struct Glaciation {
+    /// ice melted for every positive degree month, in water equivalent
+    pub melt: f64,
+    /// fraction of ice sliding to the steepest lower neighbour in a year
+    pub flow: f64,
+    /// years of growth, melting and flow
+    pub years: usize,
+    /// years between updates of the mass balance to the rising surface
+    pub update: usize,
+}
+
+impl Default for Glaciation {
+    fn default() -> Self {
+        Self {
+            melt: 0.36,
+            flow: 0.12,
+            years: 960,
+            update: 48,
+        }
+    }
+}
+
+/// yearly gain of ice from snowfall less melting, in water equivalent
+///     ice thickness lifts the surface into colder air
+pub fn mass_balance(
+    charts: &Brane<Chart>,
+    thickness: &Brane<Elevation>,
+    params: &Glaciation,
+) -> Brane<f64> {
+    charts.operate_by_index(|j| {
+        let chart = &charts.grid[j];
+        chart.snowfall().release()
+            - params.melt
+                * chart.positive_degree_months(thickness.grid[j].meters() as f64 * LAPSE_RATE)
+    })
+}
+
+/// grow ice where snow outlasts the summers and let it creep downhill
+///     ice reaching the ocean calves away
+pub fn glaciate(
+    elevation: &Brane<Elevation>,
+    ocean_level: Elevation,
+    charts: &Brane<Chart>,
+    params: &Glaciation,
+) -> Brane<Elevation> {
+    trace!("growing glaciers");
+    let resolution = elevation.resolution;
+    let one: i32 = resolution.into();
+    let ocean = ocean_tiles(elevation, ocean_level);
+    let neighbours = |j: usize| {
+        DatumZa::enravel(j, resolution)
+            .ambit_toroidal(one)
+            .map(|n| n.unravel(resolution))
+    };
+
+    let mut thickness = Brane::new(vec![0f64; resolution.square()], resolution);
+    let mut balance = Brane::new(vec![0f64; resolution.square()], resolution);
+    for year in 0..params.years {
+        if year % params.update.max(1) == 0 {
+            balance = mass_balance(
+                charts,
+                &thickness.operate_by_value_ref(|t| Elevation::confine(*t)),
+                params,
+            );
+        }
+        let grown = thickness.operate_by_index(|j| match ocean.grid[j] {
+            true => 0.,
+            false => (thickness.grid[j] + balance.grid[j] * ICE_PER_PRECIPITATION).max(0.),
+        });
+
+        // ice slides towards the steepest lower neighbour of the surface
+        let surface = |j: usize| elevation.grid[j].release() + grown.grid[j];
+        let target = grown.operate_by_index(|j| {
+            neighbours(j)
+                .into_iter()
+                .filter(|k| surface(*k) < surface(j))
+                .min_by(|a, b| surface(*a).partial_cmp(&surface(*b)).unwrap())
+        });
+        thickness = grown.operate_by_index(|j| {
+            let moving = |k: usize| match target.grid[k] {
+                Some(_) => params.flow * grown.grid[k],
+                None => 0.,
+            };
+            let inflow = neighbours(j)
+                .into_iter()
+                .filter(|k| target.grid[*k] == Some(j))
+                .map(moving)
+                .sum::<f64>();
+            match ocean.grid[j] {
+                true => 0.,
+                false => grown.grid[j] - moving(j) + inflow,
+            }
+        });
+    }
+
+    thickness.operate_by_value(Elevation::confine)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        carto::brane::Resolution,
+        units::{Precipitation, Temperature},
+    };
+    const RES: Resolution = Resolution::confine(12);
+
+    fn chart(celcius: f64) -> Chart {
+        Chart::new(
+            vec![Temperature::from_celcius(celcius); 12],
+            vec![Temperature::from_celcius(celcius - 4.); 12],
+            vec![Temperature::from_celcius(celcius + 4.); 12],
+            vec![Precipitation::confine(0.2); 12],
+            vec![Precipitation::confine(0.2); 12],
+        )
+    }
+
+    #[test]
+    fn glaciers_grow_in_the_cold() {
+        // a mountain rising from the sea, frozen above a snowline
+        let centre = DatumZa::new(6, 6);
+        let elevation = Brane::create_by_index(RES, |j| {
+            Elevation::confine(
+                0.5 - DatumZa::enravel(j, RES).dist_toroidal(&centre, RES.into()) as f64 / 16.,
+            )
+        });
+        let ocean_level = Elevation::confine(0.2);
+        let charts = elevation.operate_by_value_ref(|e| {
+            chart(match e.release() > 0.375 {
+                true => -12.,
+                false => 12.,
+            })
+        });
+        let params = Glaciation {
+            years: 240,
+            ..Glaciation::default()
+        };
+        let ice = glaciate(&elevation, ocean_level, &charts, &params);
+        let ocean = ocean_tiles(&elevation, ocean_level);
+
+        assert!(ice.grid[centre.unravel(RES)].release() > 0.);
+        assert!(ice.grid.iter().all(|t| t.release() >= 0.));
+        for j in 0..RES.square() {
+            if ocean.grid[j] {
+                assert_eq!(ice.grid[j].release(), 0.);
+            }
+        }
+        // ice creeps below the snowline
+        assert!((0..RES.square())
+            .any(|j| elevation.grid[j].release() <= 0.375 && ice.grid[j].release() > 0.));
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{
+    carto::colour::HoldridgeInk,
+    climate::{chart::Chart, classifier::Classifier},
+    units::Unit,
+};
+
+/// latitudinal and altitudinal belts by biotemperature
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Belt {
+    Polar,
+    Subpolar,
+    Boreal,
+    CoolTemperate,
+    WarmTemperate,
+    Subtropical,
+    Tropical,
+}
+
+impl Belt {
+    fn from_biotemperature(celcius: f64) -> Self {
+        match celcius {
+            t if t < 1.5 => Belt::Polar,
+            t if t < 3. => Belt::Subpolar,
+            t if t < 6. => Belt::Boreal,
+            t if t < 12. => Belt::CoolTemperate,
+            t if t < 18. => Belt::WarmTemperate,
+            t if t < 24. => Belt::Subtropical,
+            _ => Belt::Tropical,
+        }
+    }
+
+    /// life zones within the belt, from the wettest to the driest
+    fn zones(&self) -> &'static [&'static str] {
+        match self {
+            Belt::Polar => &["desert"],
+            Belt::Subpolar => &["rain tundra", "wet tundra", "moist tundra", "dry tundra"],
+            Belt::Boreal => &[
+                "rain forest",
+                "wet forest",
+                "moist forest",
+                "dry scrub",
+                "desert",
+            ],
+            Belt::CoolTemperate => &[
+                "rain forest",
+                "wet forest",
+                "moist forest",
+                "steppe",
+                "desert scrub",
+                "desert",
+            ],
+            Belt::WarmTemperate => &[
+                "rain forest",
+                "wet forest",
+                "moist forest",
+                "dry forest",
+                "thorn steppe",
+                "desert scrub",
+                "desert",
+            ],
+            Belt::Subtropical | Belt::Tropical => &[
+                "rain forest",
+                "wet forest",
+                "moist forest",
+                "dry forest",
+                "very dry forest",
+                "thorn woodland",
+                "desert scrub",
+                "desert",
+            ],
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Belt::Polar => "polar",
+            Belt::Subpolar => "subpolar",
+            Belt::Boreal => "boreal",
+            Belt::CoolTemperate => "cool temperate",
+            Belt::WarmTemperate => "warm temperate",
+            Belt::Subtropical => "subtropical",
+            Belt::Tropical => "tropical",
+        }
+    }
+}
+
+/// holdridge life zone, by belt and by humidity province
+///     the humidity counts the halvings of the potential evapotranspiration ratio
+///     from superhumid at zero, below one quarter, to superarid at seven, above sixteen
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LifeZone {
+    pub belt: Belt,
+    pub humidity: usize,
+}
+
+impl LifeZone {
+    /// drier provinces than the belt distinguishes fall into its desert
+    pub fn new(belt: Belt, humidity: usize) -> Self {
+        Self {
+            belt,
+            humidity: humidity.min(belt.zones().len() - 1),
+        }
+    }
+
+    /// the usual name, like cool temperate moist forest
+    pub fn name(&self) -> String {
+        format!("{} {}", self.belt.name(), self.belt.zones()[self.humidity])
+    }
+}
+
+/// the life zones of holdridge
+pub struct Holdridge;
+
+impl Holdridge {
+    /// mean of monthly temperatures, counting those below freezing or above thirty as zero
+    pub fn biotemperature(chart: &Chart) -> f64 {
+        chart
+            .temperature()
+            .iter()
+            .map(|t| match t.celcius() {
+                c if (0. ..=30.).contains(&c) => c,
+                _ => 0.,
+            })
+            .sum::<f64>()
+            / chart.months() as f64
+    }
+
+    /// ratio of potential evapotranspiration to annual precipitation
+    pub fn evapotranspiration_ratio(chart: &Chart) -> f64 {
+        58.93 * Self::biotemperature(chart) / (chart.annual_rainfall().release() * 324.)
+    }
+}
+
+impl Classifier for Holdridge {
+    type Class = LifeZone;
+    type Palette = HoldridgeInk;
+
+    fn classify(&self, chart: &Chart) -> LifeZone {
+        let belt = Belt::from_biotemperature(Self::biotemperature(chart));
+        let ratio = Self::evapotranspiration_ratio(chart);
+        let humidity = (0..7)
+            .find(|province| ratio < 0.25 * 2f64.powi(*province as i32))
+            .unwrap_or(7);
+        LifeZone::new(belt, humidity)
+    }
+
+    fn glacial(&self) -> LifeZone {
+        LifeZone::new(Belt::Polar, 0)
+    }
+
+    fn palette(&self) -> HoldridgeInk {
+        HoldridgeInk
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::units::{Precipitation, Temperature};
+
+    fn chart(temperature: [f64; 12], rainfall: f64) -> Chart {
+        Chart::new(
+            temperature
+                .into_iter()
+                .map(Temperature::from_celcius)
+                .collect(),
+            temperature
+                .into_iter()
+                .map(Temperature::from_celcius)
+                .collect(),
+            temperature
+                .into_iter()
+                .map(Temperature::from_celcius)
+                .collect(),
+            vec![Precipitation::confine(rainfall / 12. / 324.); 12],
+            vec![Precipitation::confine(0.24); 12],
+        )
+    }
+
+    #[test]
+    fn classify_life_zones() {
+        let zone = |temperature, rainfall| Holdridge.classify(&chart(temperature, rainfall));
+        assert_eq!(zone([27.; 12], 8000.).name(), "tropical rain forest");
+        assert_eq!(zone([28.; 12], 24.).name(), "tropical desert");
+        assert_eq!(
+            zone(
+                [-20., -18., -10., 0., 8., 14., 16., 13., 6., -2., -12., -18.],
+                480.
+            )
+            .name(),
+            "boreal moist forest"
+        );
+        assert_eq!(zone([-20.; 12], 200.), Holdridge.glacial());
+        // biotemperature ignores the heat above thirty
+        assert!(Holdridge::biotemperature(&chart([34.; 12], 1000.)) < 1.);
+    }
+}
@@ -0,0 +1,226 @@
+use crate::{
+    carto::{brane::Brane, datum::DatumZa, flux::Flux},
+    climate::river::River,
+    units::{Elevation, Precipitation, Unit},
+};
+use log::trace;
+use petgraph::{graph::NodeIndex, visit::EdgeRef, Direction};
+
+#[derive(Clone, Debug)]
+pub struct Routing {
+    /// data a flood wave travels downstream in a month
+    pub speed: f64,
+}
+
+impl Default for Routing {
+    fn default() -> Self {
+        Self { speed: 6. }
+    }
+}
+
+/// discharge of every datum through the months of a year
+pub struct Hydrographs {
+    /// discharge in every month
+    pub monthly: Vec<Brane<Precipitation>>,
+}
+
+impl Hydrographs {
+    /// discharge through the year at a datum
+    pub fn at(&self, datum: DatumZa) -> Vec<Precipitation> {
+        self.monthly
+            .iter()
+            .map(|month| month.grid[datum.unravel(month.resolution)])
+            .collect()
+    }
+
+    /// discharge through the year at every datum along a river
+    pub fn along(&self, river: &River) -> Vec<Vec<Precipitation>> {
+        river.data.iter().map(|datum| self.at(*datum)).collect()
+    }
+
+    /// month with the highest discharge at a datum
+    pub fn flood(&self, datum: DatumZa) -> usize {
+        Self::extreme(self.at(datum), |a, b| a > b)
+    }
+
+    /// month with the lowest discharge at a datum
+    pub fn drought(&self, datum: DatumZa) -> usize {
+        Self::extreme(self.at(datum), |a, b| a < b)
+    }
+
+    fn extreme(hydrograph: Vec<Precipitation>, beyond: fn(f64, f64) -> bool) -> usize {
+        hydrograph
+            .iter()
+            .enumerate()
+            .fold(0, |best, (month, discharge)| {
+                if beyond(discharge.release(), hydrograph[best].release()) {
+                    month
+                } else {
+                    best
+                }
+            })
+    }
+
+    /// discharge in every month, for export
+    pub fn into_branes(self) -> Vec<Brane<Precipitation>> {
+        self.monthly
+    }
+}
+
+/// route monthly runoff down the slope
+///     runoff reaches a datum downstream after its distance over the speed in months,
+///     split between the two nearest months, and a wave travelling for a whole year
+///     arrives in the same month again
+pub fn hydrographs(
+    slope: &Flux<Elevation>,
+    runoff: &[Brane<Precipitation>],
+    params: &Routing,
+) -> Hydrographs {
+    fn route_at_node(
+        node: NodeIndex,
+        flows: &mut [Vec<f64>],
+        slope: &Flux<Elevation>,
+        runoff: &[Brane<Precipitation>],
+        speed: f64,
+        period: usize,
+    ) -> Vec<Vec<f64>> {
+        // recursive like shed, with the year of runoff kept apart by the steps it travelled
+        let months = runoff.len();
+        let jndex = slope.graph[node].unravel(slope.resolution);
+        let mut travelled = vec![runoff
+            .iter()
+            .map(|month| month.grid[jndex].release())
+            .collect::<Vec<f64>>()];
+        let sources = slope
+            .graph
+            .edges_directed(node, Direction::Incoming)
+            .map(|edge| edge.source())
+            .collect::<Vec<NodeIndex>>();
+        for source in sources {
+            let upstream = route_at_node(source, flows, slope, runoff, speed, period);
+            for (steps, year) in upstream.into_iter().enumerate() {
+                let steps = (steps + 1) % period;
+                if travelled.len() <= steps {
+                    travelled.resize(steps + 1, vec![0.; months]);
+                }
+                for (month, discharge) in year.into_iter().enumerate() {
+                    travelled[steps][month] += discharge;
+                }
+            }
+        }
+        flows[jndex] = (0..months)
+            .map(|month| {
+                travelled
+                    .iter()
+                    .enumerate()
+                    .map(|(steps, year)| {
+                        let delay = steps as f64 / speed;
+                        let (whole, part) = (delay.floor() as usize % months, delay.fract());
+                        (1. - part) * year[(month + months - whole) % months]
+                            + part * year[(month + 2 * months - whole - 1) % months]
+                    })
+                    .sum::<f64>()
+            })
+            .collect();
+        travelled
+    }
+
+    trace!("routing monthly runoff");
+    assert!(params.speed > 0., "flood waves need to travel downstream");
+    let resolution = slope.resolution;
+    let months = runoff.len();
+    // steps after which a wave has travelled for a whole year
+    let period = ((params.speed * months as f64).round() as usize).max(1);
+    let mut flows = vec![vec![0.; months]; resolution.square()];
+    for node in slope.roots.clone() {
+        route_at_node(node, &mut flows, slope, runoff, params.speed, period);
+    }
+    Hydrographs {
+        monthly: (0..months)
+            .map(|month| {
+                Brane::create_by_index(resolution, |j| Precipitation::confine(flows[j][month]))
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::carto::{brane::Resolution, honeycomb::HoneyCellToroidal};
+    use float_eq::assert_float_eq;
+    const RES: Resolution = Resolution::confine(12);
+
+    #[test]
+    fn flood_arrives_late_downstream() {
+        let outlet = DatumZa::new(0, 0);
+        let elevation = Brane::create_by_index(RES, |j| {
+            Elevation::confine(
+                DatumZa::enravel(j, RES).dist_toroidal(&outlet, RES.into()) as f64 / 12.,
+            )
+        });
+        let slope = Flux::from(elevation);
+        // all the runoff comes in the first month
+        let runoff = (0..12)
+            .map(|month| {
+                Brane::create_by_index(RES, |_| {
+                    Precipitation::confine(if month == 0 { 1. } else { 0. })
+                })
+            })
+            .collect::<Vec<Brane<Precipitation>>>();
+        let hydrographs = hydrographs(&slope, &runoff, &Routing::default());
+
+        // nothing is lost on the way
+        let total = hydrographs
+            .at(outlet)
+            .iter()
+            .map(|discharge| discharge.release())
+            .sum::<f64>();
+        assert_float_eq!(total, RES.square() as f64, rmax <= 0.000001);
+
+        let source = DatumZa::new(6, 0);
+        assert_eq!(hydrographs.flood(source), 0);
+        assert!(hydrographs.at(outlet)[1] > hydrographs.at(source)[1]);
+        assert_eq!(hydrographs.into_branes().len(), 12);
+    }
+
+    #[test]
+    fn flood_travels_at_speed() {
+        let outlet = DatumZa::new(0, 0);
+        let source = DatumZa::new(6, 0);
+        let elevation = Brane::create_by_index(RES, |j| {
+            Elevation::confine(
+                DatumZa::enravel(j, RES).dist_toroidal(&outlet, RES.into()) as f64 / 12.,
+            )
+        });
+        let slope = Flux::from(elevation);
+        // a single flood at the source, six steps upstream of the outlet
+        let runoff = (0..12)
+            .map(|month| {
+                Brane::create_by_index(RES, |j| {
+                    Precipitation::confine(if month == 0 && j == source.unravel(RES) {
+                        1.
+                    } else {
+                        0.
+                    })
+                })
+            })
+            .collect::<Vec<Brane<Precipitation>>>();
+        let at_outlet = |speed: f64| {
+            hydrographs(&slope, &runoff, &Routing { speed })
+                .at(outlet)
+                .iter()
+                .map(|discharge| discharge.release())
+                .collect::<Vec<f64>>()
+        };
+
+        // two months at three data a month
+        let slow = at_outlet(3.);
+        assert_float_eq!(slow[2], 1., abs <= 0.000001);
+        assert_float_eq!(slow.iter().sum::<f64>(), 1., abs <= 0.000001);
+        // a month and a half at four data a month
+        let fast = at_outlet(4.);
+        assert_float_eq!(fast[1], 0.5, abs <= 0.000001);
+        assert_float_eq!(fast[2], 0.5, abs <= 0.000001);
+    }
+}
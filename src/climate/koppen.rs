@@ -0,0 +1,249 @@
+use crate::{
+    carto::colour::KoppenInk,
+    climate::{chart::Chart, classifier::Classifier},
+    units::Unit,
+};
+
+/// climate classes of köppen and geiger
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Koppen {
+    Af,
+    Am,
+    Aw,
+    As,
+    BWh,
+    BWk,
+    BSh,
+    BSk,
+    Csa,
+    Csb,
+    Csc,
+    Cwa,
+    Cwb,
+    Cwc,
+    Cfa,
+    Cfb,
+    Cfc,
+    Dsa,
+    Dsb,
+    Dsc,
+    Dsd,
+    Dwa,
+    Dwb,
+    Dwc,
+    Dwd,
+    Dfa,
+    Dfb,
+    Dfc,
+    Dfd,
+    ET,
+    EF,
+}
+
+impl Koppen {
+    /// the usual letter code, like Cfb
+    pub fn code(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+/// the scheme of köppen and geiger
+pub struct KoppenGeiger;
+
+impl Classifier for KoppenGeiger {
+    type Class = Koppen;
+    type Palette = KoppenInk;
+
+    fn classify(&self, chart: &Chart) -> Koppen {
+        Koppen::from(chart)
+    }
+
+    fn glacial(&self) -> Koppen {
+        Koppen::EF
+    }
+
+    fn palette(&self) -> KoppenInk {
+        KoppenInk
+    }
+}
+
+impl From<&Chart> for Koppen {
+    /// classify following peel, finlayson and mcmahon
+    ///     every month of the chart counts as a twelfth of the year
+    fn from(chart: &Chart) -> Self {
+        let months = chart.months();
+        let scale = 12. / months.max(1) as f64;
+        let temperature = chart
+            .temperature()
+            .iter()
+            .map(|t| t.celcius())
+            .collect::<Vec<f64>>();
+        let rainfall = chart
+            .rainfall()
+            .iter()
+            .map(|r| r.release() * 324.)
+            .collect::<Vec<f64>>();
+
+        let mean = temperature.iter().sum::<f64>() / months as f64;
+        let hottest = temperature.iter().copied().fold(f64::NAN, f64::max);
+        let coldest = temperature.iter().copied().fold(f64::NAN, f64::min);
+        let warm = temperature.iter().filter(|t| **t >= 10.).count() as f64 * scale;
+
+        let summer = chart.summer();
+        let season = |in_summer: bool| -> (f64, f64, f64) {
+            // returns total, driest and wettest month
+            rainfall
+                .iter()
+                .enumerate()
+                .filter(|(month, _)| summer[*month] == in_summer)
+                .fold((0., f64::INFINITY, f64::NEG_INFINITY), |acc, (_, r)| {
+                    (acc.0 + r, acc.1.min(*r), acc.2.max(*r))
+                })
+        };
+        let (summer_total, summer_dry, summer_wet) = season(true);
+        let (winter_total, winter_dry, winter_wet) = season(false);
+        let annual = (summer_total + winter_total) * scale;
+        let driest = summer_dry.min(winter_dry);
+
+        // aridity threshold in centimeters, higher when rain falls in the heat
+        let threshold = if winter_total >= 0.7 * (summer_total + winter_total) {
+            2. * mean
+        } else if summer_total >= 0.7 * (summer_total + winter_total) {
+            2. * mean + 28.
+        } else {
+            2. * mean + 14.
+        };
+
+        if hottest < 10. {
+            if hottest > 0. {
+                Koppen::ET
+            } else {
+                Koppen::EF
+            }
+        } else if annual < 10. * threshold {
+            match (annual < 5. * threshold, mean >= 18.) {
+                (true, true) => Koppen::BWh,
+                (true, false) => Koppen::BWk,
+                (false, true) => Koppen::BSh,
+                (false, false) => Koppen::BSk,
+            }
+        } else if coldest >= 18. {
+            if driest >= 60. {
+                Koppen::Af
+            } else if driest >= 100. - annual / 25. {
+                Koppen::Am
+            } else if summer_dry < winter_dry {
+                Koppen::As
+            } else {
+                Koppen::Aw
+            }
+        } else {
+            let temperate = coldest > 0.;
+            let dryness = if summer_dry < 40. && summer_dry < winter_wet / 3. {
+                's'
+            } else if winter_dry < summer_wet / 10. {
+                'w'
+            } else {
+                'f'
+            };
+            let heat = if hottest >= 22. {
+                'a'
+            } else if warm >= 4. {
+                'b'
+            } else if !temperate && coldest < -38. {
+                'd'
+            } else {
+                'c'
+            };
+            match (temperate, dryness, heat) {
+                (true, 's', 'a') => Koppen::Csa,
+                (true, 's', 'b') => Koppen::Csb,
+                (true, 's', _) => Koppen::Csc,
+                (true, 'w', 'a') => Koppen::Cwa,
+                (true, 'w', 'b') => Koppen::Cwb,
+                (true, 'w', _) => Koppen::Cwc,
+                (true, _, 'a') => Koppen::Cfa,
+                (true, _, 'b') => Koppen::Cfb,
+                (true, _, _) => Koppen::Cfc,
+                (false, 's', 'a') => Koppen::Dsa,
+                (false, 's', 'b') => Koppen::Dsb,
+                (false, 's', 'c') => Koppen::Dsc,
+                (false, 's', _) => Koppen::Dsd,
+                (false, 'w', 'a') => Koppen::Dwa,
+                (false, 'w', 'b') => Koppen::Dwb,
+                (false, 'w', 'c') => Koppen::Dwc,
+                (false, 'w', _) => Koppen::Dwd,
+                (false, _, 'a') => Koppen::Dfa,
+                (false, _, 'b') => Koppen::Dfb,
+                (false, _, 'c') => Koppen::Dfc,
+                (false, _, _) => Koppen::Dfd,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::units::{Precipitation, Temperature};
+
+    fn chart(temperature: [f64; 12], rainfall: [f64; 12]) -> Chart {
+        Chart::new(
+            temperature
+                .into_iter()
+                .map(Temperature::from_celcius)
+                .collect(),
+            temperature
+                .into_iter()
+                .map(|t| Temperature::from_celcius(t - 6.))
+                .collect(),
+            temperature
+                .into_iter()
+                .map(|t| Temperature::from_celcius(t + 6.))
+                .collect(),
+            rainfall
+                .into_iter()
+                .map(|mm| Precipitation::confine(mm / 324.))
+                .collect(),
+            vec![Precipitation::confine(0.24); 12],
+        )
+    }
+
+    #[test]
+    fn classify_charts() {
+        let koppen = |temperature, rainfall| Koppen::from(&chart(temperature, rainfall));
+        assert_eq!(koppen([27.; 12], [250.; 12]), Koppen::Af);
+        assert_eq!(koppen([28.; 12], [2.; 12]), Koppen::BWh);
+        assert_eq!(
+            koppen(
+                [10., 11., 13., 16., 20., 24., 27., 27., 24., 19., 14., 11.],
+                [100., 90., 70., 40., 20., 5., 2., 5., 20., 60., 90., 110.]
+            ),
+            Koppen::Csa
+        );
+        assert_eq!(
+            koppen(
+                [4., 5., 7., 9., 12., 15., 17., 17., 14., 11., 7., 5.],
+                [70.; 12]
+            ),
+            Koppen::Cfb
+        );
+        assert_eq!(
+            koppen(
+                [-20., -18., -10., 0., 8., 14., 16., 13., 6., -2., -12., -18.],
+                [40.; 12]
+            ),
+            Koppen::Dfc
+        );
+        assert_eq!(
+            koppen(
+                [-25., -25., -20., -12., -4., 2., 5., 3., -3., -12., -20., -24.],
+                [20.; 12]
+            ),
+            Koppen::ET
+        );
+        assert_eq!(koppen([-20.; 12], [20.; 12]), Koppen::EF);
+        assert_eq!(Koppen::Cfb.code(), "Cfb");
+    }
+}
@@ -0,0 +1,227 @@
+use crate::{
+    carto::{
+        brane::Brane,
+        datum::DatumZa,
+        honeycomb::{Direction, HoneyCellToroidal},
+    },
+    climate::atmosphere::Wind,
+    units::{Elevation, Precipitation, Temperature, Unit},
+};
+use log::trace;
+
+#[derive(Clone, Debug)]
+pub struct Advection {
+    /// fraction of atmospheric water raining out at a datum, before temperature
+    pub rainout: f64,
+    /// fraction of rainfall on land evaporating back into the air
+    pub recycling: f64,
+    /// extra rainout for every kilometre of rise along the wind
+    pub orographic: f64,
+    /// largest number of iterations towards the steady state
+    pub iterations: usize,
+    /// relative change of atmospheric water taken as steady
+    pub tolerance: f64,
+}
+
+impl Default for Advection {
+    fn default() -> Self {
+        Self {
+            rainout: 0.24,
+            recycling: 0.48,
+            orographic: 1.,
+            iterations: 240,
+            tolerance: 0.0000_01,
+        }
+    }
+}
+
+/// steady water cycle of the atmosphere
+pub struct Moisture {
+    /// water held in the atmosphere
+    pub water: Brane<f64>,
+    pub evaporation: Brane<Precipitation>,
+    pub precipitation: Brane<Precipitation>,
+    /// extra rainout from air forced up slopes
+    pub lift: Brane<f64>,
+}
+
+impl Moisture {
+    /// difference between global evaporation and precipitation, relative to evaporation
+    pub fn imbalance(&self) -> f64 {
+        let evaporation = self
+            .evaporation
+            .grid
+            .iter()
+            .map(|e| e.release())
+            .sum::<f64>();
+        let precipitation = self
+            .precipitation
+            .grid
+            .iter()
+            .map(|p| p.release())
+            .sum::<f64>();
+        (evaporation - precipitation).abs() / evaporation
+    }
+
+    /// precipitation through the adjustment curve the charts and their thresholds are tuned to
+    ///     the balance itself is kept on the unadjusted precipitation
+    pub fn rainfall(&self) -> Brane<Precipitation> {
+        let adjustment_curve =
+            |x: &Precipitation| Precipitation::confine(2f64.powf(x.release()) - 1.);
+        self.precipitation.operate_by_value_ref(adjustment_curve)
+    }
+}
+
+/// carry water evaporated from the oceans along the winds until it rains out
+///     iterates until the atmosphere holds a steady amount of water
+pub fn advect(
+    altitude_above_ocean: &Brane<Elevation>,
+    temperature: &Brane<Temperature>,
+    evaporation_potential: &Brane<Precipitation>,
+    winds: &Brane<Wind>,
+    ocean_tiles: &Brane<bool>,
+    params: &Advection,
+) -> Moisture {
+    let resolution = evaporation_potential.resolution;
+    let one: i32 = resolution.into();
+
+    // air rising along the wind cools and rains out
+    let lift = winds.operate_by_index(|j| {
+        let downwind = DatumZa::enravel(j, resolution)
+            .neighbour_toroidal(winds.grid[j].direction, one)
+            .unravel(resolution);
+        let rise = altitude_above_ocean.grid[downwind].meters().max(0)
+            - altitude_above_ocean.grid[j].meters().max(0);
+        rise.max(0) as f64 / 1000. * winds.grid[j].magnitude.min(1.)
+    });
+
+    // cold air holds less water
+    let rainout = temperature.operate_by_index(|j| {
+        let capacity = (0.06 * (temperature.grid[j].celcius() - 15.)).exp().min(2.);
+        1. - (-(params.rainout / capacity + params.orographic * lift.grid[j])).exp()
+    });
+
+    // share of the remaining water blown to every neighbour
+    let carry = winds.operate_by_value_ref(|wind| {
+        let shares = Direction::array().map(|direction| wind.towards(direction));
+        let total = shares.iter().sum::<f64>();
+        let moving = wind.magnitude / (1. + wind.magnitude);
+        match total > 0. {
+            true => shares.map(|share| moving * share / total),
+            false => [0.; 6],
+        }
+    });
+
+    let source = |j: usize, rain: f64| -> f64 {
+        let potential = evaporation_potential.grid[j].release();
+        match ocean_tiles.grid[j] {
+            true => potential,
+            false => params.recycling * rain.min(potential),
+        }
+    };
+
+    let mut water = Brane::new(vec![0f64; resolution.square()], resolution);
+    for iteration in 0..params.iterations {
+        let next = water.operate_by_index(|j| {
+            let here = DatumZa::enravel(j, resolution);
+            let left = |k: usize| water.grid[k] * (1. - rainout.grid[k]);
+            let inflow = Direction::array()
+                .iter()
+                .map(|direction| {
+                    let k = here.neighbour_toroidal(*direction, one).unravel(resolution);
+                    left(k) * carry.grid[k][(direction.index() + 3) % 6]
+                })
+                .sum::<f64>();
+            source(j, water.grid[j] * rainout.grid[j])
+                + left(j) * (1. - carry.grid[j].iter().sum::<f64>())
+                + inflow
+        });
+        let change = next
+            .grid
+            .iter()
+            .zip(water.grid.iter())
+            .map(|(a, b)| (a - b).abs())
+            .fold(0., f64::max);
+        let scale = next.grid.iter().copied().fold(f64::MIN_POSITIVE, f64::max);
+        water = next;
+        if change < params.tolerance * scale {
+            trace!("atmospheric water settled after {} iterations", iteration);
+            break;
+        }
+    }
+
+    let precipitation = water.operate_by_index(|j| water.grid[j] * rainout.grid[j]);
+    Moisture {
+        evaporation: precipitation
+            .operate_by_index(|j| Precipitation::confine(source(j, precipitation.grid[j]))),
+        precipitation: precipitation.operate_by_value(Precipitation::confine),
+        water,
+        lift,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::carto::brane::Resolution;
+    const RES: Resolution = Resolution::confine(12);
+
+    fn world(ridge: bool) -> Moisture {
+        // ocean on one half, land with an optional ridge on the other
+        let altitude = Brane::create_by_index(RES, |j| {
+            let x = DatumZa::enravel(j, RES).x;
+            Elevation::confine(match (x, ridge) {
+                (0..6, _) => -0.1,
+                (9, true) => 0.24,
+                _ => 0.01,
+            })
+        });
+        let ocean = altitude.operate_by_value_ref(|a| a.release() < 0.);
+        let temperature = Brane::create_by_index(RES, |_| Temperature::from_celcius(18.));
+        let potential = Brane::create_by_index(RES, |_| Precipitation::confine(0.5));
+        let winds = Brane::create_by_index(RES, |_| Wind {
+            direction: Direction::Xp,
+            magnitude: 1.,
+        });
+        advect(
+            &altitude,
+            &temperature,
+            &potential,
+            &winds,
+            &ocean,
+            &Advection::default(),
+        )
+    }
+
+    #[test]
+    fn evaporation_matches_precipitation() {
+        let moisture = world(true);
+        assert!(moisture.imbalance() < 0.001);
+        assert!(moisture
+            .precipitation
+            .grid
+            .iter()
+            .all(|p| p.release() >= 0.));
+    }
+
+    #[test]
+    fn ridges_rain_out_upwind() {
+        let flat = world(false);
+        let ridge = world(true);
+        let upwind = DatumZa::new(8, 4).unravel(RES);
+        let downwind = DatumZa::new(10, 4).unravel(RES);
+        assert!(ridge.lift.grid[upwind] > 0.);
+        assert!(ridge.precipitation.grid[upwind] > flat.precipitation.grid[upwind]);
+        assert!(ridge.precipitation.grid[downwind] < flat.precipitation.grid[downwind]);
+    }
+
+    #[test]
+    fn rainfall_follows_the_adjustment_curve() {
+        let moisture = world(true);
+        let rainfall = moisture.rainfall();
+        for (rain, precipitation) in rainfall.grid.iter().zip(moisture.precipitation.grid.iter()) {
+            let p = precipitation.release();
+            assert!((rain.release() - (2f64.powf(p) - 1.)).abs() < 0.000001);
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{
+    carto::{
+        brane::{Brane, Resolution},
+        colour as clr,
+        render::Renderable,
+    },
+    climate::{
+        atmosphere::{pressure, winds},
+        circulation::{
+            day_length, diurnal_range, evapotranspiration_potential, temperature_at_altitude,
+            temperature_at_ocean_level, SimulationConfig,
+        },
+        moisture::advect,
+        ocean::{gyres, sea_surface_temperature, temperature_with_currents},
+        orbit::Orbit,
+    },
+    regression::predict_brane,
+    units::{Elevation, Precipitation, Temperature, Unit},
+};
+
+/// climate of every datum over a single month
+pub struct Month {
+    pub temp: Brane<Temperature>,
+    /// mean daily minimum temperature
+    pub temp_min: Brane<Temperature>,
+    /// mean daily maximum temperature
+    pub temp_max: Brane<Temperature>,
+    pub rain: Brane<Precipitation>,
+    pub pevt: Brane<Precipitation>,
+    /// fraction of the day spent in daylight
+    pub day: Brane<f64>,
+    /// sea surface temperature
+    pub sst: Brane<Temperature>,
+    /// water equivalent of the snow lying on the ground
+    pub snow: Brane<Precipitation>,
+    pub sea_ice: Brane<bool>,
+}
+
+impl Month {
+    pub fn new(
+        temp: Brane<Temperature>,
+        range: &Brane<f64>,
+        rain: Brane<Precipitation>,
+        pevt: Brane<Precipitation>,
+        day: Brane<f64>,
+        sst: Brane<Temperature>,
+    ) -> Self {
+        let extreme = |sign: f64| {
+            temp.operate_by_index(|j| {
+                Temperature::from_celcius(temp.grid[j].celcius() + sign * range.grid[j] / 2.)
+            })
+        };
+        let resolution = temp.resolution;
+        Self {
+            temp_min: extreme(-1.),
+            temp_max: extreme(1.),
+            temp,
+            rain,
+            pevt,
+            day,
+            sst,
+            snow: Brane::create_by_index(resolution, |_| Precipitation::confine(0.)),
+            sea_ice: Brane::create_by_index(resolution, |_| false),
+        }
+    }
+
+    /// cool the month under snow and ice, given in degrees celcius
+    pub fn cool(
+        self,
+        cooling: &Brane<f64>,
+        snow: Brane<Precipitation>,
+        sea_ice: Brane<bool>,
+    ) -> Self {
+        let shift = |temp: &Brane<Temperature>| {
+            temp.operate_by_index(|j| {
+                Temperature::from_celcius(temp.grid[j].celcius() - cooling.grid[j])
+            })
+        };
+        let temp = shift(&self.temp);
+        Self {
+            temp_min: shift(&self.temp_min),
+            temp_max: shift(&self.temp_max),
+            pevt: evapotranspiration_potential(&temp),
+            temp,
+            snow,
+            sea_ice,
+            ..self
+        }
+    }
+
+    pub fn upscale(
+        self,
+        orbit: &Orbit,
+        altitude_smol: &Brane<Elevation>,
+        continentality_smol: &Brane<f64>,
+        altitude: &Brane<Elevation>,
+        continentality: &Brane<f64>,
+    ) -> Self {
+        let temperature = predict_brane(
+            &self.temp.release(),
+            altitude_smol,
+            continentality_smol,
+            altitude,
+            continentality,
+        )
+        .operate_by_value(Temperature::confine);
+        let day = self.day.upscale_raw(altitude.resolution);
+        Self::new(
+            temperature.clone(),
+            &diurnal_range(&day, continentality, altitude, orbit),
+            self.rain.upscale(altitude.resolution),
+            evapotranspiration_potential(&temperature),
+            day,
+            self.sst.upscale(altitude.resolution),
+        )
+    }
+
+    pub fn render(&self) {
+        self.temp.stats();
+        self.temp
+            .upscale(Resolution::confine(324))
+            .render("temp".to_string(), clr::CelciusInk);
+        self.rain.stats();
+        self.rain
+            .upscale(Resolution::confine(324))
+            .render("rain".to_string(), clr::MoonMeterInk);
+        self.pevt.stats();
+        self.pevt
+            .upscale(Resolution::confine(324))
+            .render("pevt".to_string(), clr::MoonMeterInk);
+        self.snow
+            .upscale(Resolution::confine(324))
+            .render("snow".to_string(), clr::MoonMeterInk);
+    }
+}
+
+/// climate of a month at a given time of the year
+pub fn simulate_month(
+    solar_time: f64,
+    orbit: &Orbit,
+    altitude: &Brane<Elevation>,
+    continentality: &Brane<f64>,
+    temperature_average: &Brane<Temperature>,
+    ocean_tiles: &Brane<bool>,
+    config: &SimulationConfig,
+) -> Month {
+    let temperature_at_ocean =
+        temperature_at_ocean_level(solar_time, orbit, temperature_average, continentality);
+    let winds = winds(
+        &pressure(
+            &temperature_at_altitude(&temperature_at_ocean, altitude),
+            altitude,
+            &config.atmosphere,
+        ),
+        &config.atmosphere,
+    );
+
+    // currents move heat along the coasts
+    let sst = sea_surface_temperature(
+        &temperature_at_ocean,
+        &gyres(&winds, ocean_tiles, &config.currents),
+        ocean_tiles,
+        &config.currents,
+    );
+    let temperature = temperature_at_altitude(
+        &temperature_with_currents(&temperature_at_ocean, &sst, ocean_tiles, continentality),
+        altitude,
+    );
+    let evaporation_potential = evapotranspiration_potential(&temperature);
+    let day = day_length(altitude.resolution, solar_time, orbit);
+    Month::new(
+        temperature.clone(),
+        &diurnal_range(&day, continentality, altitude, orbit),
+        advect(
+            altitude,
+            &temperature,
+            &evaporation_potential,
+            &winds,
+            ocean_tiles,
+            &config.advection,
+        )
+        .rainfall(),
+        evaporation_potential,
+        day,
+        sst,
+    )
+}
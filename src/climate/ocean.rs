@@ -0,0 +1,251 @@
+use crate::{
+    carto::{
+        brane::Brane,
+        datum::{DatumRe, DatumZa},
+        honeycomb::{Direction, HoneyCellToroidal},
+    },
+    climate::atmosphere::{heading, Wind},
+    units::{Temperature, Unit},
+};
+use log::trace;
+use std::collections::VecDeque;
+
+#[derive(Clone, Debug)]
+pub struct Currents {
+    /// speed of the fastest current, driven by the wind stress
+    pub drag: f64,
+    /// how quickly surface water takes the temperature of the air above it
+    pub relaxation: f64,
+    /// iterations for the gyres and for the heat they carry
+    pub iterations: usize,
+}
+
+impl Default for Currents {
+    fn default() -> Self {
+        Self {
+            drag: 1.,
+            relaxation: 0.5,
+            iterations: 96,
+        }
+    }
+}
+
+/// rotate a vector by a right angle clockwise
+fn clockwise(vector: DatumRe) -> DatumRe {
+    DatumRe::new(vector.y, -vector.x)
+}
+
+/* # gyres */
+
+/// surface currents driven by the curl of the wind stress, closed by the coasts
+///     solves for a stream function vanishing on land and follows its contours
+pub fn gyres(winds: &Brane<Wind>, ocean_tiles: &Brane<bool>, params: &Currents) -> Brane<DatumRe> {
+    trace!("spinning up ocean gyres");
+    let resolution = winds.resolution;
+    let one: i32 = resolution.into();
+    let neighbours = |j: usize| {
+        let here = DatumZa::enravel(j, resolution);
+        Direction::array().map(|direction| {
+            (
+                direction,
+                here.neighbour_toroidal(direction, one).unravel(resolution),
+            )
+        })
+    };
+
+    // circulation of the wind around every datum
+    let curl = winds.operate_by_index(|j| {
+        neighbours(j)
+            .iter()
+            .map(|(direction, k)| {
+                let tangent = clockwise(heading(*direction)) * -1.;
+                let wind = winds.grid[*k].vector();
+                wind.x * tangent.x + wind.y * tangent.y
+            })
+            .sum::<f64>()
+    });
+
+    // jacobi iterations on the poisson equation of the stream function
+    let mut stream = Brane::new(vec![0f64; resolution.square()], resolution);
+    for _ in 0..params.iterations {
+        stream = stream.operate_by_index(|j| match ocean_tiles.grid[j] {
+            true => {
+                (neighbours(j)
+                    .iter()
+                    .map(|(_, k)| stream.grid[*k])
+                    .sum::<f64>()
+                    + curl.grid[j])
+                    / 6.
+            }
+            false => 0.,
+        });
+    }
+
+    // currents flow along the contours of the stream function
+    let currents = stream.operate_by_index(|j| match ocean_tiles.grid[j] {
+        true => clockwise(
+            neighbours(j)
+                .iter()
+                .map(|(direction, k)| heading(*direction) * (stream.grid[*k] - stream.grid[j]))
+                .fold(DatumRe::new(0., 0.), |a, b| a + b)
+                / 3.,
+        ),
+        false => DatumRe::new(0., 0.),
+    });
+    let fastest = currents
+        .grid
+        .iter()
+        .map(|current| (current.x.powi(2) + current.y.powi(2)).sqrt())
+        .fold(0., f64::max);
+    match fastest > 0. {
+        true => currents.operate_by_value(|current| current * (params.drag / fastest)),
+        false => currents,
+    }
+}
+
+/* # sea surface temperature */
+
+/// temperature of the surface water, carried along the currents
+pub fn sea_surface_temperature(
+    temperature_at_ocean: &Brane<Temperature>,
+    currents: &Brane<DatumRe>,
+    ocean_tiles: &Brane<bool>,
+    params: &Currents,
+) -> Brane<Temperature> {
+    trace!("carrying heat along ocean currents");
+    let resolution = temperature_at_ocean.resolution;
+    let one: i32 = resolution.into();
+    let equilibrium = temperature_at_ocean.release();
+    let mut sst = equilibrium.clone();
+    for _ in 0..params.iterations {
+        sst = sst.operate_by_index(|j| {
+            if !ocean_tiles.grid[j] {
+                return equilibrium.grid[j];
+            }
+            let current = currents.grid[j];
+            let here = DatumZa::enravel(j, resolution);
+            // water arrives from the neighbours the current points away from
+            let (heat, weight) = Direction::array()
+                .iter()
+                .map(|direction| {
+                    let k = here.neighbour_toroidal(*direction, one).unravel(resolution);
+                    let h = heading(*direction);
+                    let weight = match ocean_tiles.grid[k] {
+                        true => (-(current.x * h.x + current.y * h.y)).max(0.),
+                        false => 0.,
+                    };
+                    (sst.grid[k] * weight, weight)
+                })
+                .fold((0., 0.), |a, b| (a.0 + b.0, a.1 + b.1));
+            match weight > 0. {
+                true => {
+                    let speed = (current.x.powi(2) + current.y.powi(2)).sqrt();
+                    let carried = speed / (speed + params.relaxation);
+                    (1. - carried) * equilibrium.grid[j] + carried * heat / weight
+                }
+                false => equilibrium.grid[j],
+            }
+        });
+    }
+    sst.operate_by_value(Temperature::confine)
+}
+
+/// air temperature at the ocean level warmed or cooled by nearby currents
+///     land takes the anomaly of the nearest ocean, fading with continentality
+pub fn temperature_with_currents(
+    temperature_at_ocean: &Brane<Temperature>,
+    sst: &Brane<Temperature>,
+    ocean_tiles: &Brane<bool>,
+    continentality: &Brane<f64>,
+) -> Brane<Temperature> {
+    let resolution = temperature_at_ocean.resolution;
+    let one: i32 = resolution.into();
+
+    // nearest ocean datum to every datum
+    let mut nearest = Brane::new(vec![None::<usize>; resolution.square()], resolution);
+    let mut queue = VecDeque::new();
+    for jndex in 0..resolution.square() {
+        if ocean_tiles.grid[jndex] {
+            nearest.grid[jndex] = Some(jndex);
+            queue.push_back(DatumZa::enravel(jndex, resolution));
+        }
+    }
+    while let Some(here) = queue.pop_front() {
+        let source = nearest.grid[here.unravel(resolution)];
+        for neighbour in here.ambit_toroidal(one) {
+            let jndex = neighbour.unravel(resolution);
+            if nearest.grid[jndex].is_none() {
+                nearest.grid[jndex] = source;
+                queue.push_back(neighbour);
+            }
+        }
+    }
+
+    temperature_at_ocean.operate_by_index(|j| match (ocean_tiles.grid[j], nearest.grid[j]) {
+        (true, _) => sst.grid[j],
+        (false, Some(k)) => {
+            temperature_at_ocean.grid[j]
+                + (sst.grid[k] - temperature_at_ocean.grid[k])
+                    * (1. - continentality.grid[j]).clamp(0., 1.)
+        }
+        (false, None) => temperature_at_ocean.grid[j],
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::carto::brane::Resolution;
+    const RES: Resolution = Resolution::confine(12);
+
+    #[test]
+    fn gyres_stay_at_sea() {
+        // a basin closed by a strip of land, with winds sheared across it
+        let ocean = Brane::create_by_index(RES, |j| DatumZa::enravel(j, RES).x > 1);
+        let winds = Brane::create_by_index(RES, |j| Wind {
+            direction: match DatumZa::enravel(j, RES).y < 6 {
+                true => Direction::Yp,
+                false => Direction::Yn,
+            },
+            magnitude: 1.,
+        });
+        let currents = gyres(&winds, &ocean, &Currents::default());
+        for j in 0..RES.square() {
+            let current = currents.grid[j];
+            let speed = (current.x.powi(2) + current.y.powi(2)).sqrt();
+            assert!(speed <= 1. + 0.000001);
+            if !ocean.grid[j] {
+                assert_eq!(speed, 0.);
+            }
+        }
+        assert!(currents.grid.iter().any(|c| c.x != 0. || c.y != 0.));
+    }
+
+    #[test]
+    fn currents_carry_heat() {
+        let ocean = Brane::create_by_index(RES, |_| true);
+        let air = Brane::create_by_index(RES, |j| {
+            Temperature::confine(match DatumZa::enravel(j, RES).x < 6 {
+                true => 0.75,
+                false => 0.25,
+            })
+        });
+        let still = Brane::create_by_index(RES, |_| DatumRe::new(0., 0.));
+        let flowing = Brane::create_by_index(RES, |_| heading(Direction::Xp));
+        let params = Currents::default();
+        let downstream = DatumZa::new(6, 3).unravel(RES);
+
+        let sst = sea_surface_temperature(&air, &still, &ocean, &params);
+        assert_eq!(sst.grid[downstream], air.grid[downstream]);
+        let sst = sea_surface_temperature(&air, &flowing, &ocean, &params);
+        assert!(sst.grid[downstream] > air.grid[downstream]);
+
+        // the coast next to a warm current is warmer than its air alone
+        let island = Brane::create_by_index(RES, |j| DatumZa::enravel(j, RES).x != 7);
+        let sst = sea_surface_temperature(&air, &flowing, &island, &params);
+        let continentality = Brane::create_by_index(RES, |_| 0.);
+        let warmed = temperature_with_currents(&air, &sst, &island, &continentality);
+        let coast = DatumZa::new(7, 3).unravel(RES);
+        assert!(warmed.grid[coast] > air.grid[coast]);
+    }
+}
@@ -0,0 +1,232 @@
+use crate::carto::{datum::DatumRe, honeycomb::Hexagon};
+use std::f64::consts::TAU;
+
+/// orbit of the world around its star, and the light it receives
+#[derive(Clone, Debug)]
+pub struct Orbit {
+    pub eccentricity: f64,
+    /// in units of the torus
+    pub semi_major_axis: f64,
+    /// angle of the periapsis around the focus
+    pub periapsis: f64,
+    /// scales the received insolation
+    pub luminosity: f64,
+    /// exponent of the insolation falloff away from the sub-solar point
+    pub falloff: f64,
+    /// rotation period, relative to the reference world
+    pub day: f64,
+}
+
+impl Default for Orbit {
+    fn default() -> Self {
+        Self {
+            eccentricity: 0.42,
+            semi_major_axis: 0.36,
+            periapsis: -TAU * 3f64.recip(),
+            luminosity: 1.,
+            falloff: 1.,
+            day: 1.,
+        }
+    }
+}
+
+impl Orbit {
+    /// eccentric anomaly at a given time of the year, solving the kepler equation
+    pub fn eccentric_anomaly(&self, time: f64) -> f64 {
+        let mean = TAU * time;
+        let mut anomaly = if self.eccentricity < 0.8 {
+            mean
+        } else {
+            TAU * 2f64.recip()
+        };
+        // newton iteration on E - e sin E - M
+        for _ in 0..32 {
+            let step = (anomaly - self.eccentricity * anomaly.sin() - mean)
+                / (1. - self.eccentricity * anomaly.cos());
+            anomaly -= step;
+            if step.abs() < 1e-12 {
+                break;
+            }
+        }
+        anomaly
+    }
+
+    /// angle between the periapsis and the world, seen from the star
+    pub fn true_anomaly(&self, time: f64) -> f64 {
+        let anomaly = self.eccentric_anomaly(time);
+        2. * ((1. + self.eccentricity).sqrt() * (anomaly * 2f64.recip()).sin())
+            .atan2((1. - self.eccentricity).sqrt() * (anomaly * 2f64.recip()).cos())
+    }
+
+    /// position on the orbit relative to the star, in hexagon centre coordinates
+    pub fn position(&self, time: f64) -> DatumRe {
+        let radius_major = self.semi_major_axis;
+        let angle = self.periapsis;
+
+        let linear = self.eccentricity * radius_major;
+        let radius_minor = (radius_major.powi(2) - linear.powi(2)).sqrt();
+        let anomaly = self.eccentric_anomaly(time);
+
+        let centre = DatumRe::new(linear * angle.cos(), linear * angle.sin());
+        centre
+            - DatumRe::new(
+                radius_major * angle.cos() * anomaly.cos(),
+                radius_major * angle.sin() * anomaly.cos(),
+            )
+            + DatumRe::new(
+                radius_minor * -angle.sin() * anomaly.sin(),
+                radius_minor * angle.cos() * anomaly.sin(),
+            )
+    }
+
+    /// point under the star at a given time of the year, inside the unit square
+    pub fn subsolar(&self, time: f64) -> DatumRe {
+        let point = self.position(time).uncentre();
+        DatumRe::new(point.x.rem_euclid(1.), point.y.rem_euclid(1.))
+    }
+
+    /// insolation received at a given ground distance from the sub-solar point
+    fn received(&self, distance: f64) -> f64 {
+        // encodes the relationship between the ground distance between points
+        //    and the received insolation
+        let curve = (TAU * 4f64.recip() * distance).cos();
+        self.luminosity * curve.signum() * curve.abs().powf(self.falloff)
+    }
+
+    /// insolation received at a datum at a given time of the year
+    pub fn insolation(&self, datum: DatumRe, time: f64) -> f64 {
+        self.received(datum.distance(&self.subsolar(time)))
+    }
+
+    /// insolation received at a datum at a given hour of a day
+    ///     as the world turns, the sub-solar point sweeps along the first axis of the torus
+    pub fn insolation_at_hour(&self, datum: DatumRe, time: f64, hour: f64) -> f64 {
+        let subsolar = self.subsolar(time);
+        self.received(datum.distance(&DatumRe::new(
+            (subsolar.x + hour).rem_euclid(1.),
+            subsolar.y,
+        )))
+    }
+
+    /// fraction of a day spent in daylight at a given time of the year
+    pub fn day_length(&self, datum: DatumRe, time: f64) -> f64 {
+        // the star is up while it gives at least half of its light
+        let hours = 24;
+        (0..hours)
+            .filter(|hour| {
+                self.insolation_at_hour(datum, time, *hour as f64 / hours as f64)
+                    > self.luminosity * 2f64.recip()
+            })
+            .count() as f64
+            / hours as f64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use float_eq::assert_float_eq;
+    const EPSILON: f64 = 0.0000_01;
+
+    #[test]
+    fn insolation_scales_with_luminosity() {
+        let datum = DatumRe::new(0.25, 0.5);
+        let orbit = Orbit::default();
+        let brighter = Orbit {
+            luminosity: 2.,
+            ..Orbit::default()
+        };
+        for time in [0., 0.25, 0.5, 0.75] {
+            assert_float_eq!(
+                2. * orbit.insolation(datum, time),
+                brighter.insolation(datum, time),
+                abs <= EPSILON
+            );
+        }
+    }
+
+    #[test]
+    fn insolation_peaks_under_the_star() {
+        let orbit = Orbit {
+            falloff: 2.,
+            ..Orbit::default()
+        };
+        for time in [0., 0.3, 0.6] {
+            assert_float_eq!(
+                orbit.insolation(orbit.subsolar(time), time),
+                1.,
+                abs <= EPSILON
+            );
+        }
+    }
+
+    #[test]
+    fn day_length_follows_the_star() {
+        let orbit = Orbit::default();
+        let subsolar = orbit.subsolar(0.);
+        let near = orbit.day_length(subsolar, 0.);
+        let far = orbit.day_length(
+            DatumRe::new(subsolar.x, (subsolar.y + 0.5).rem_euclid(1.)),
+            0.,
+        );
+        assert!((0. ..=1.).contains(&near) && (0. ..=1.).contains(&far));
+        assert!(near > far);
+        assert_float_eq!(
+            near,
+            orbit.day_length(
+                DatumRe::new((subsolar.x + 0.25).rem_euclid(1.), subsolar.y),
+                0.
+            ),
+            abs <= 1. / 24.
+        );
+    }
+
+    #[test]
+    fn kepler_equation() {
+        for eccentricity in [0., 0.42, 0.9] {
+            let orbit = Orbit {
+                eccentricity,
+                ..Orbit::default()
+            };
+            for step in 0..24 {
+                let time = step as f64 / 24.;
+                let anomaly = orbit.eccentric_anomaly(time);
+                assert_float_eq!(
+                    anomaly - eccentricity * anomaly.sin(),
+                    TAU * time,
+                    abs <= EPSILON
+                );
+            }
+            assert_float_eq!(orbit.true_anomaly(0.), 0., abs <= EPSILON);
+            assert_float_eq!(
+                orbit.true_anomaly(0.5).abs(),
+                TAU * 2f64.recip(),
+                abs <= EPSILON
+            );
+        }
+    }
+
+    #[test]
+    fn equal_areal_velocity() {
+        let orbit = Orbit::default();
+        let step = 1024f64.recip();
+        let swept = |time: f64| {
+            let (a, b) = (orbit.position(time), orbit.position(time + step));
+            (a.x * b.y - a.y * b.x).abs() * 2f64.recip()
+        };
+        let expected = swept(0.);
+        for index in 1..64 {
+            assert_float_eq!(swept(index as f64 / 64.), expected, rmax <= 0.001);
+        }
+        // the whole ellipse is swept during a year
+        // product of the semi-major and the semi-minor axis
+        let axes = orbit.semi_major_axis.powi(2) * (1. - orbit.eccentricity.powi(2)).sqrt();
+        assert_float_eq!(
+            (0..1024)
+                .map(|index| swept(index as f64 * step))
+                .sum::<f64>(),
+            TAU * 2f64.recip() * axes,
+            rmax <= 0.001
+        );
+    }
+}
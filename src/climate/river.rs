@@ -0,0 +1,310 @@
+use crate::{
+    carto::{
+        brane::{Brane, Resolution},
+        datum::{DatumRe, DatumZa},
+        flux::Flux,
+        honeycomb::{self, Hexagon, HoneyCellPlanar, HoneyCellToroidal},
+        render::render_rivers,
+    },
+    units::{Elevation, Precipitation, Unit},
+};
+use log::trace;
+use petgraph::{graph::NodeIndex, Direction};
+use std::{collections::HashMap, fs, path::Path};
+
+/* # rivers */
+
+/// a stretch of river between two confluences, a source or a mouth
+pub struct River {
+    /// data along the course, in the direction of flow
+    pub data: Vec<DatumZa>,
+    /// discharge between every pair of consecutive data
+    pub discharge: Vec<Precipitation>,
+    pub strahler: usize,
+    pub shreve: usize,
+    /// index of the river this one flows into
+    pub outlet: Option<usize>,
+}
+
+impl River {
+    /// data along the course, unwrapped from the torus so that the line is continuous
+    pub fn course(&self, resolution: Resolution) -> Vec<DatumZa> {
+        let mut course = vec![self.data[0]];
+        for pair in self.data.windows(2) {
+            let heading = honeycomb::Direction::array()
+                .into_iter()
+                .find(|heading| pair[0].neighbour_toroidal(*heading, resolution.into()) == pair[1])
+                .expect("consecutive data along a river are neighbours");
+            course.push(course[course.len() - 1].neighbour_planar(heading));
+        }
+        course
+    }
+
+    /// hexagon centres along the course
+    pub fn line(&self, resolution: Resolution) -> Vec<DatumRe> {
+        self.course(resolution)
+            .iter()
+            .map(|datum| datum.centre())
+            .collect::<Vec<DatumRe>>()
+    }
+}
+
+pub struct Rivers {
+    pub rivers: Vec<River>,
+    pub confluences: Vec<DatumZa>,
+    pub mouths: Vec<DatumZa>,
+    pub resolution: Resolution,
+}
+
+impl Rivers {
+    /// trace rivers wherever the discharge exceeds a threshold
+    pub fn extract(
+        slope: &Flux<Elevation>,
+        shed: &Brane<Precipitation>,
+        ocean_tiles: &Brane<bool>,
+        threshold: Precipitation,
+    ) -> Self {
+        trace!("extracting rivers");
+        let resolution = slope.resolution;
+        let wet = |node: NodeIndex| -> bool {
+            let jndex = slope.graph[node].unravel(resolution);
+            !ocean_tiles.grid[jndex] && shed.grid[jndex] >= threshold
+        };
+        let upstream = |node: NodeIndex| -> usize {
+            slope
+                .graph
+                .neighbors_directed(node, Direction::Incoming)
+                .filter(|source| wet(*source))
+                .count()
+        };
+        let downstream = |node: NodeIndex| -> Option<NodeIndex> {
+            slope
+                .graph
+                .neighbors_directed(node, Direction::Outgoing)
+                .next()
+        };
+
+        // stream orders accumulated from the sources down
+        let order = slope.breadth();
+        let mut orders = HashMap::<NodeIndex, (usize, usize)>::new();
+        for node in order.iter().rev().copied().filter(|node| wet(*node)) {
+            let tributaries = slope
+                .graph
+                .neighbors_directed(node, Direction::Incoming)
+                .filter_map(|source| orders.get(&source).copied())
+                .collect::<Vec<(usize, usize)>>();
+            let strahler = match tributaries.iter().map(|(s, _)| *s).max() {
+                Some(highest) => match tributaries.iter().filter(|(s, _)| *s == highest).count() {
+                    1 => highest,
+                    _ => highest + 1,
+                },
+                None => 1,
+            };
+            let shreve = tributaries.iter().map(|(_, s)| *s).sum::<usize>().max(1);
+            orders.insert(node, (strahler, shreve));
+        }
+
+        // every source and every confluence starts a new river
+        let mut rivers = Vec::<River>::new();
+        let mut starts = HashMap::<DatumZa, usize>::new();
+        let mut confluences = Vec::<DatumZa>::new();
+        let mut mouths = Vec::<DatumZa>::new();
+        for start in order
+            .iter()
+            .rev()
+            .copied()
+            .filter(|node| wet(*node) && upstream(*node) != 1)
+        {
+            if upstream(start) > 1 {
+                confluences.push(slope.graph[start]);
+            }
+            let mut nodes = vec![start];
+            let mut node = start;
+            while let Some(next) = downstream(node) {
+                nodes.push(next);
+                if !wet(next) {
+                    // the river reaches the ocean
+                    mouths.push(slope.graph[node]);
+                    break;
+                }
+                if upstream(next) > 1 {
+                    break;
+                }
+                node = next;
+            }
+            if downstream(node).is_none() {
+                // the river ends in a sink
+                mouths.push(slope.graph[node]);
+            }
+
+            let (strahler, shreve) = orders[&start];
+            starts.insert(slope.graph[start], rivers.len());
+            rivers.push(River {
+                data: nodes.iter().map(|node| slope.graph[*node]).collect(),
+                discharge: nodes[..nodes.len() - 1]
+                    .iter()
+                    .map(|node| shed.grid[slope.graph[*node].unravel(resolution)])
+                    .collect(),
+                strahler,
+                shreve,
+                outlet: None,
+            });
+        }
+
+        // link every river to the one starting at its confluence
+        for river in rivers.iter_mut() {
+            let last = river.data[river.data.len() - 1];
+            river.outlet = starts.get(&last).copied().filter(|_| river.data.len() > 1);
+        }
+
+        Self {
+            rivers,
+            confluences,
+            mouths,
+            resolution,
+        }
+    }
+
+    /// largest discharge found along any river
+    pub fn discharge_max(&self) -> Precipitation {
+        self.rivers
+            .iter()
+            .flat_map(|river| river.discharge.iter().copied())
+            .fold(Precipitation::confine(0.), |a, b| if a < b { b } else { a })
+    }
+
+    /* ## export */
+
+    /// draw the network with strokes scaled by discharge
+    pub fn render(&self, variable: String) {
+        let discharge_max = self.discharge_max().release().max(f64::EPSILON);
+        let courses = self
+            .rivers
+            .iter()
+            .map(|river| river.course(self.resolution))
+            .collect::<Vec<Vec<DatumZa>>>();
+        let widths = self
+            .rivers
+            .iter()
+            .map(|river| {
+                river
+                    .discharge
+                    .iter()
+                    .map(|discharge| 0.12 + 0.48 * (discharge.release() / discharge_max).sqrt())
+                    .collect::<Vec<f64>>()
+            })
+            .collect::<Vec<Vec<f64>>>();
+        render_rivers(&courses, &widths, self.resolution, variable);
+    }
+
+    /// describe the network as a geojson feature collection
+    pub fn geojson(&self) -> String {
+        let scale = f64::from(self.resolution).recip();
+        let point = |datum: DatumRe| format!("[{}, {}]", datum.x * scale, datum.y * scale);
+        let feature = |kind: &str, coordinates: String, properties: String| {
+            format!(
+                "{{\"type\": \"Feature\", \"geometry\": {{\"type\": \"{}\", \"coordinates\": {}}}, \"properties\": {{{}}}}}",
+                kind, coordinates, properties
+            )
+        };
+
+        let mut features = self
+            .rivers
+            .iter()
+            .map(|river| {
+                feature(
+                    "LineString",
+                    format!(
+                        "[{}]",
+                        river
+                            .line(self.resolution)
+                            .into_iter()
+                            .map(point)
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    ),
+                    format!(
+                        "\"discharge\": [{}], \"strahler\": {}, \"shreve\": {}",
+                        river
+                            .discharge
+                            .iter()
+                            .map(|discharge| discharge.release().to_string())
+                            .collect::<Vec<String>>()
+                            .join(", "),
+                        river.strahler,
+                        river.shreve,
+                    ),
+                )
+            })
+            .collect::<Vec<String>>();
+        for (kind, data) in [("confluence", &self.confluences), ("mouth", &self.mouths)] {
+            features.extend(data.iter().map(|datum| {
+                feature(
+                    "Point",
+                    point(datum.centre()),
+                    format!("\"kind\": \"{}\"", kind),
+                )
+            }));
+        }
+
+        format!(
+            "{{\"type\": \"FeatureCollection\", \"features\": [{}]}}",
+            features.join(", ")
+        )
+    }
+
+    /// save the network to a .geojson file
+    pub fn save_geojson(&self, variable: String) {
+        let path_name = format!("bounce/{}-{}.geojson", variable, self.resolution.release());
+        trace!("saving rivers to {}", path_name);
+        fs::create_dir_all("bounce").unwrap();
+        fs::write(Path::new(&path_name), self.geojson()).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    const RES: Resolution = Resolution::confine(6);
+
+    #[test]
+    fn rivers_orders() {
+        // a single cone draining into the origin, which is the only ocean
+        let elevation = Brane::create_by_index(RES, |j| {
+            Elevation::confine(
+                DatumZa::enravel(j, RES).dist_toroidal(&DatumZa::new(0, 0), RES.into()) as f64,
+            )
+        });
+        let ocean_tiles = elevation.operate_by_value_ref(|value| value.release() < 0.5);
+        let rainfall = Brane::create_by_index(RES, |_| Precipitation::confine(1.));
+        let slope = Flux::from(elevation);
+        let shed = crate::climate::circulation::shed(&slope, &rainfall);
+
+        let rivers = Rivers::extract(&slope, &shed, &ocean_tiles, Precipitation::confine(0.));
+        assert!(!rivers.rivers.is_empty());
+        assert!(!rivers.mouths.is_empty());
+        assert!(rivers
+            .rivers
+            .iter()
+            .all(|river| river.discharge.len() + 1 == river.data.len()));
+        assert_eq!(
+            rivers
+                .rivers
+                .iter()
+                .filter(|river| river.outlet.is_none())
+                .map(|river| river.shreve)
+                .sum::<usize>(),
+            rivers
+                .rivers
+                .iter()
+                .filter(|river| river.strahler == 1)
+                .count()
+        );
+        for river in rivers.rivers.iter() {
+            if let Some(outlet) = river.outlet {
+                assert!(rivers.rivers[outlet].strahler >= river.strahler);
+                assert!(rivers.rivers[outlet].shreve > river.shreve);
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{
+    carto::brane::Brane,
+    climate::{
+        chart::Chart,
+        circulation::{ocean_tiles, SimulationConfig},
+        cosmos::Cosmos,
+        orbit::Orbit,
+        vegetation::Vege,
+    },
+    units::Elevation,
+};
+use log::trace;
+use std::collections::HashMap;
+
+/// differences between two variants of the same world
+pub struct Shift {
+    /// data which were land and are now under the ocean
+    pub flooded: Brane<bool>,
+    /// data which were under the ocean and are now land
+    pub exposed: Brane<bool>,
+    /// number of data changing from one vegetation class to another
+    pub transitions: HashMap<(Option<Vege>, Option<Vege>), usize>,
+}
+
+impl Shift {
+    /// compare two simulated variants of the same world
+    pub fn between(before: &Cosmos, after: &Cosmos) -> Self {
+        let ocean_before = ocean_tiles(&before.altitude, before.ocean_level);
+        let ocean_after = ocean_tiles(&after.altitude, after.ocean_level);
+        let mut transitions = HashMap::new();
+        for (vege_before, vege_after) in before
+            .vege()
+            .grid
+            .into_iter()
+            .zip(after.vege().grid.into_iter())
+            .filter(|(b, a)| b != a)
+        {
+            *transitions.entry((vege_before, vege_after)).or_insert(0) += 1;
+        }
+
+        Self {
+            flooded: ocean_after.operate_by_index(|j| ocean_after.grid[j] && !ocean_before.grid[j]),
+            exposed: ocean_after.operate_by_index(|j| !ocean_after.grid[j] && ocean_before.grid[j]),
+            transitions,
+        }
+    }
+
+    pub fn stats(&self) {
+        println!("statistics for shift");
+        println!(
+            "    flooded:    {}",
+            self.flooded.grid.iter().filter(|f| **f).count()
+        );
+        println!(
+            "    exposed:    {}",
+            self.exposed.grid.iter().filter(|e| **e).count()
+        );
+        let mut transitions = self.transitions.iter().collect::<Vec<_>>();
+        transitions.sort_by(|a, b| b.1.cmp(a.1));
+        for ((before, after), count) in transitions {
+            println!("    {:?} -> {:?}:  {}", before, after, count);
+        }
+    }
+}
+
+/// move the ocean level of a simulated world and simulate it again
+pub fn ocean_level_scenario(
+    cosmos: &Cosmos,
+    orbit: &Orbit,
+    config: &SimulationConfig,
+    delta: Elevation,
+) -> (Cosmos, Shift) {
+    trace!("shifting ocean level by {} meters", delta.meters());
+    let resolution = cosmos.altitude.resolution;
+    let shifted = Cosmos {
+        ice: cosmos.ice.clone(),
+        ..Cosmos::new(
+            cosmos.altitude.clone(),
+            cosmos.ocean_level + delta,
+            Brane::create_by_index(resolution, |_| Chart::empty(config.months)),
+        )
+    }
+    .simulate(orbit, config);
+    let shift = Shift::between(cosmos, &shifted);
+    (shifted, shift)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        carto::brane::Resolution,
+        units::{Precipitation, Temperature, Unit},
+    };
+    const RES: Resolution = Resolution::confine(6);
+
+    #[test]
+    fn shift_between() {
+        let altitude =
+            Brane::create_by_index(RES, |j| Elevation::confine(j as f64 / RES.square() as f64));
+        let charts = Brane::create_by_index(RES, |_| {
+            Chart::new(
+                vec![Temperature::from_celcius(24.)],
+                vec![Temperature::from_celcius(18.)],
+                vec![Temperature::from_celcius(30.)],
+                vec![Precipitation::confine(1.)],
+                vec![Precipitation::confine(1.)],
+            )
+        });
+        let before = Cosmos::new(altitude.clone(), Elevation::confine(0.25), charts.clone());
+        let after = Cosmos::new(altitude, Elevation::confine(0.5), charts);
+        let shift = Shift::between(&before, &after);
+
+        let flooded = shift.flooded.grid.iter().filter(|f| **f).count();
+        assert_eq!(flooded, 9);
+        assert!(shift.exposed.grid.iter().all(|e| !*e));
+        assert_eq!(shift.transitions.len(), 1);
+        assert_eq!(shift.transitions.values().sum::<usize>(), flooded);
+        assert!(shift.transitions.keys().all(|(_, a)| a.is_none()));
+    }
+}
@@ -1,4 +1,10 @@
-use crate::climate::chart::Zone;
+use crate::{
+    carto::colour::VegeInk,
+    climate::{
+        chart::{Chart, Zone},
+        classifier::Classifier,
+    },
+};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum Thermo {
@@ -27,15 +33,27 @@ enum Parallaxo {
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct ZoneType(Thermo, Igro, Parallaxo);
 
+impl Thermo {
+    /// from the mean and warmest month in degrees celcius and the fraction of days with frost
+    fn new(mean: f64, warmest: f64, frost: f64) -> Self {
+        match (mean, warmest, frost) {
+            // never thaws
+            (_, w, _) if w < 0. => Thermo::Frigid,
+            // summers too short or cool for anything but hardy plants
+            (_, w, f) if w < 10. || f > 0.48 => Thermo::Cold,
+            // a proper frosty winter
+            (_, _, f) if f > 0.12 => Thermo::Brisk,
+            // occasional frost or a mild year
+            (t, _, f) if f > 0.01 || t < 20. => Thermo::Warm,
+            (t, _, _) if t >= 20. => Thermo::Hot,
+            _ => Thermo::Frigid,
+        }
+    }
+}
+
 impl From<Zone> for ZoneType {
     fn from(zone: Zone) -> Self {
-        let thermos = match zone.thermos.celcius() {
-            t if (30.0..f64::INFINITY).contains(&t) => Thermo::Hot,
-            t if (21.0..30.).contains(&t) => Thermo::Warm,
-            t if (12.0..21.).contains(&t) => Thermo::Brisk,
-            t if (-2.0..12.).contains(&t) => Thermo::Cold,
-            _ => Thermo::Frigid,
-        };
+        let thermos = Thermo::new(zone.thermos.celcius(), zone.warmest.celcius(), zone.frost);
         let igros = match zone.igros {
             i if (-0.03..f64::INFINITY).contains(&i) => Igro::Jungle,
             i if (-0.24..-0.03).contains(&i) => Igro::Forest,
@@ -51,7 +69,7 @@ impl From<Zone> for ZoneType {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Vege {
     Stone,
     Frost,
@@ -67,6 +85,26 @@ pub enum Vege {
     Broadleaf,
 }
 
+/// the native scheme of twelve classes
+pub struct Vegetation;
+
+impl Classifier for Vegetation {
+    type Class = Vege;
+    type Palette = VegeInk;
+
+    fn classify(&self, chart: &Chart) -> Vege {
+        Vege::from(ZoneType::from(Zone::from(chart)))
+    }
+
+    fn glacial(&self) -> Vege {
+        Vege::Frost
+    }
+
+    fn palette(&self) -> VegeInk {
+        VegeInk
+    }
+}
+
 /*
 /// how many people can this zone sustain
 pub fn habitability(cell: Option<Vege>) -> f64 {
@@ -124,3 +162,50 @@ impl From<ZoneType> for Vege {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    const EPSILON: f64 = 0.001;
+
+    #[test]
+    fn thermo_thresholds() {
+        // mean and warmest month in degrees celcius, fraction of days with frost
+        let table = [
+            // warmest month at freezing
+            ((-8., -EPSILON, 0.6), Thermo::Frigid),
+            ((-8., 0., 0.6), Thermo::Cold),
+            ((-8., EPSILON, 0.6), Thermo::Cold),
+            // warmest month at ten degrees
+            ((4., 10. - EPSILON, 0.2), Thermo::Cold),
+            ((4., 10., 0.2), Thermo::Brisk),
+            ((4., 10. + EPSILON, 0.2), Thermo::Brisk),
+            // frost on almost half of the days
+            ((6., 18., 0.48 - EPSILON), Thermo::Brisk),
+            ((6., 18., 0.48), Thermo::Brisk),
+            ((6., 18., 0.48 + EPSILON), Thermo::Cold),
+            // frost on an eighth of the days
+            ((12., 24., 0.12 - EPSILON), Thermo::Warm),
+            ((12., 24., 0.12), Thermo::Warm),
+            ((12., 24., 0.12 + EPSILON), Thermo::Brisk),
+            // frost on a hundredth of the days
+            ((24., 30., 0.01 - EPSILON), Thermo::Hot),
+            ((24., 30., 0.01), Thermo::Hot),
+            ((24., 30., 0.01 + EPSILON), Thermo::Warm),
+            // mean of twenty degrees
+            ((20. - EPSILON, 30., 0.), Thermo::Warm),
+            ((20., 30., 0.), Thermo::Hot),
+            ((20. + EPSILON, 30., 0.), Thermo::Hot),
+        ];
+        for ((mean, warmest, frost), expected) in table {
+            assert_eq!(
+                Thermo::new(mean, warmest, frost),
+                expected,
+                "mean {} warmest {} frost {}",
+                mean,
+                warmest,
+                frost
+            );
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{
+    carto::colour::WhittakerInk,
+    climate::{chart::Chart, classifier::Classifier},
+    units::Unit,
+};
+
+/// biomes of whittaker's diagram
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Biome {
+    Tundra,
+    BorealForest,
+    TemperateGrassland,
+    Woodland,
+    TemperateSeasonalForest,
+    TemperateRainForest,
+    SubtropicalDesert,
+    TropicalSeasonalForest,
+    TropicalRainForest,
+}
+
+/// lookup of biomes on mean temperature and annual precipitation
+pub struct Whittaker;
+
+impl Whittaker {
+    /// biome at a mean temperature in degrees celcius and annual precipitation in centimeters
+    pub fn lookup(celcius: f64, centimeters: f64) -> Biome {
+        match (celcius, centimeters) {
+            (t, _) if t < -5. => Biome::Tundra,
+            (t, p) if t < 3. => match p {
+                p if p < 10. + 2. * t => Biome::Tundra,
+                _ => Biome::BorealForest,
+            },
+            (t, p) if t < 20. => match p {
+                p if p < 10. + 2. * t => Biome::TemperateGrassland,
+                p if p < 40. + 3. * t => Biome::Woodland,
+                p if p < 220. => Biome::TemperateSeasonalForest,
+                _ => Biome::TemperateRainForest,
+            },
+            (t, p) => match p {
+                p if p < 40. + 2. * (t - 20.) => Biome::SubtropicalDesert,
+                p if p < 250. => Biome::TropicalSeasonalForest,
+                _ => Biome::TropicalRainForest,
+            },
+        }
+    }
+}
+
+impl Classifier for Whittaker {
+    type Class = Biome;
+    type Palette = WhittakerInk;
+
+    fn classify(&self, chart: &Chart) -> Biome {
+        Self::lookup(
+            chart.thermos().celcius(),
+            chart.annual_rainfall().release() * 32.4,
+        )
+    }
+
+    fn glacial(&self) -> Biome {
+        Biome::Tundra
+    }
+
+    fn palette(&self) -> WhittakerInk {
+        WhittakerInk
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lookup_biomes() {
+        assert_eq!(Whittaker::lookup(-12., 40.), Biome::Tundra);
+        assert_eq!(Whittaker::lookup(0., 60.), Biome::BorealForest);
+        assert_eq!(Whittaker::lookup(10., 20.), Biome::TemperateGrassland);
+        assert_eq!(Whittaker::lookup(10., 60.), Biome::Woodland);
+        assert_eq!(Whittaker::lookup(10., 120.), Biome::TemperateSeasonalForest);
+        assert_eq!(Whittaker::lookup(12., 300.), Biome::TemperateRainForest);
+        assert_eq!(Whittaker::lookup(26., 20.), Biome::SubtropicalDesert);
+        assert_eq!(Whittaker::lookup(26., 150.), Biome::TropicalSeasonalForest);
+        assert_eq!(Whittaker::lookup(26., 400.), Biome::TropicalRainForest);
+    }
+}
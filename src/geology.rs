@@ -0,0 +1,7 @@
+pub mod bedrock;
+pub mod continents;
+pub mod erosion;
+pub mod isostasy;
+pub mod soil;
+pub mod tectonics;
+pub mod volcanism;
@@ -0,0 +1,303 @@
+use crate::{
+    carto::{
+        brane::Brane,
+        datum::{DatumRe, DatumZa},
+    },
+    climate::circulation::OCNLV,
+    units::{Elevation, Unit},
+};
+use log::trace;
+use noise::{Billow, NoiseFn, OpenSimplex, Perlin, RidgedMulti, Seedable, Worley};
+use serde::{Deserialize, Serialize};
+use splines::{Interpolation, Key, Spline};
+use std::{f64::consts::TAU, fs, path::Path};
+
+const SQRT3B2: f64 = 0.8660254;
+
+/* # recipes */
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum NoiseKind {
+    OpenSimplex,
+    Perlin,
+    Worley,
+    RidgedMulti,
+    Billow,
+}
+
+/// a single layer of fractal noise
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct NoiseRecipe {
+    pub kind: NoiseKind,
+    /// amplitude of every octave
+    pub amplitudes: Vec<f64>,
+    /// frequency of every octave
+    pub frequencies: Vec<f64>,
+    /// how strongly the layer warps its own domain
+    pub warp: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct BedrockConfig {
+    /// exponent of the distance from the ocean level scaling the noise
+    pub contrast: f64,
+    /// smallest scaling of the noise, reached around the ocean level
+    pub contrast_floor: f64,
+    /// keys of the redistribution curve, as pairs of input and output elevation
+    pub curve: Vec<(f64, f64)>,
+    /// level around which the noise is weakest
+    #[serde(default = "default_ocean_level")]
+    pub ocean_level: f64,
+    pub layers: Vec<NoiseRecipe>,
+}
+
+/// ocean level of configs saved before it was configurable
+fn default_ocean_level() -> f64 {
+    OCNLV
+}
+
+impl Default for BedrockConfig {
+    fn default() -> Self {
+        Self::around(default_ocean_level())
+    }
+}
+
+impl BedrockConfig {
+    /// the default recipe, with its curve bent around a given ocean level
+    pub fn around(ocean_level: f64) -> Self {
+        let step = 256f64.recip();
+        Self {
+            contrast: 0.72,
+            contrast_floor: 0.06,
+            curve: vec![
+                (0., 0.),
+                (ocean_level - 0.08, ocean_level - 12.0 * step),
+                (ocean_level, ocean_level),
+                (ocean_level + 0.04, ocean_level + 8.0 * step),
+                (1., 1.),
+            ],
+            ocean_level,
+            layers: vec![NoiseRecipe {
+                kind: NoiseKind::OpenSimplex,
+                amplitudes: vec![
+                    2f64.recip(),
+                    4f64.recip(),
+                    8f64.recip(),
+                    12f64.recip(),
+                    24f64.recip(),
+                    48f64.recip(),
+                    96f64.recip(),
+                    192f64.recip(),
+                ],
+                frequencies: vec![3., 6., 12., 24., 48., 96., 192., 384.],
+                warp: 1.,
+            }],
+        }
+    }
+
+    /// save config to a .toml file
+    pub fn save(&self, variable: String) {
+        let path_name = format!("static/{}.toml", variable);
+        trace!("saving bedrock config to {}", path_name);
+        fs::create_dir_all("static").unwrap();
+        fs::write(
+            Path::new(&path_name),
+            toml::to_string(self).expect("config should serialise"),
+        )
+        .unwrap();
+    }
+
+    /// load config with a given name from a .toml file
+    pub fn load(variable: String) -> Self {
+        let path_name = format!("static/{}.toml", variable);
+        trace!("loading bedrock config from {}", path_name);
+        toml::from_str(&fs::read_to_string(Path::new(&path_name)).unwrap())
+            .expect("config file is malformed")
+    }
+}
+
+/* # sampling */
+
+enum Sampler {
+    OpenSimplex(OpenSimplex),
+    Perlin(Perlin),
+    Worley(Worley),
+    RidgedMulti(RidgedMulti),
+    Billow(Billow),
+}
+
+impl Sampler {
+    fn new(kind: NoiseKind, seed: u32) -> Self {
+        match kind {
+            NoiseKind::OpenSimplex => Self::OpenSimplex(OpenSimplex::new().set_seed(seed)),
+            NoiseKind::Perlin => Self::Perlin(Perlin::new().set_seed(seed)),
+            NoiseKind::Worley => Self::Worley(Worley::new().set_seed(seed)),
+            NoiseKind::RidgedMulti => Self::RidgedMulti(RidgedMulti::new().set_seed(seed)),
+            NoiseKind::Billow => Self::Billow(Billow::new().set_seed(seed)),
+        }
+    }
+
+    fn get(&self, point: [f64; 4]) -> f64 {
+        match self {
+            Self::OpenSimplex(noise) => noise.get(point),
+            Self::Perlin(noise) => noise.get(point),
+            Self::Worley(noise) => noise.get(point),
+            Self::RidgedMulti(noise) => noise.get(point),
+            Self::Billow(noise) => noise.get(point),
+        }
+    }
+}
+
+struct Layer<'a> {
+    sampler: Sampler,
+    recipe: &'a NoiseRecipe,
+}
+
+impl Layer<'_> {
+    fn fractional_brownian_motion(&self, x: f64, y: f64) -> f64 {
+        self.recipe
+            .amplitudes
+            .iter()
+            .zip(self.recipe.frequencies.iter())
+            .map(|(amplitude, frequency)| {
+                amplitude
+                    * self.sampler.get([
+                        // toroidal wrapping
+                        frequency * x.cos(),
+                        frequency * x.sin(),
+                        frequency * SQRT3B2 * y.cos(), // undistort geometry on the hexagon
+                        frequency * SQRT3B2 * y.sin(), // undistort geometry on the hexagon
+                    ])
+            })
+            .sum::<f64>()
+    }
+
+    fn sample(&self, datum: &DatumRe) -> f64 {
+        let x: f64 = TAU * datum.x;
+        let y: f64 = TAU * (datum.x + datum.y); // undistort geometry on the hexagon
+        let warp = self.recipe.warp * self.fractional_brownian_motion(x, y);
+        self.fractional_brownian_motion(x + warp, y + warp)
+    }
+}
+
+/* # bedrock generation */
+
+/// perturb a base elevation with the noise described in a config
+pub fn bedrock_from(
+    elevation_base: &Brane<Elevation>,
+    seed: u32,
+    config: &BedrockConfig,
+) -> Brane<Elevation> {
+    let elevation_base = elevation_base.release();
+
+    // prepeare noise
+    let layers = config
+        .layers
+        .iter()
+        .enumerate()
+        .map(|(index, recipe)| Layer {
+            sampler: Sampler::new(recipe.kind, seed.wrapping_add(index as u32)),
+            recipe,
+        })
+        .collect::<Vec<Layer>>();
+
+    // prepare curve
+    let elevation_curve: Spline<f64, f64> = Spline::from_vec(
+        config
+            .curve
+            .iter()
+            .map(|(input, output)| Key::new(*input, *output, Interpolation::Linear))
+            .collect(),
+    );
+
+    elevation_base.operate_by_index(|jndex| {
+        let base = elevation_base.grid[jndex];
+        let datum =
+            DatumZa::enravel(jndex, elevation_base.resolution).cast(elevation_base.resolution);
+        let noise = layers.iter().map(|layer| layer.sample(&datum)).sum::<f64>();
+        Elevation::confine(
+            elevation_curve
+                .clamped_sample(
+                    base + noise
+                        * (base - config.ocean_level)
+                            .abs()
+                            .powf(config.contrast)
+                            .max(config.contrast_floor),
+                )
+                .unwrap(),
+        )
+    })
+}
+
+/// perturb the base elevation loaded from file
+pub fn bedrock(seed: u32, config: &BedrockConfig) -> Brane<Elevation> {
+    bedrock_from(
+        &Brane::<Elevation>::load("elevation-base".to_string()),
+        seed,
+        config,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::carto::brane::Resolution;
+    const RES: Resolution = Resolution::confine(12);
+
+    #[test]
+    fn config_roundtrip() {
+        let config = BedrockConfig {
+            layers: vec![
+                NoiseRecipe {
+                    kind: NoiseKind::RidgedMulti,
+                    amplitudes: vec![0.5, 0.25],
+                    frequencies: vec![2., 4.],
+                    warp: 0.,
+                },
+                NoiseRecipe {
+                    kind: NoiseKind::Worley,
+                    amplitudes: vec![0.125],
+                    frequencies: vec![8.],
+                    warp: 0.5,
+                },
+            ],
+            ..BedrockConfig::default()
+        };
+        let text = toml::to_string(&config).unwrap();
+        assert_eq!(toml::from_str::<BedrockConfig>(&text).unwrap(), config);
+    }
+
+    #[test]
+    fn config_without_ocean_level() {
+        let config = BedrockConfig::around(0.5);
+        let text = toml::to_string(&config)
+            .unwrap()
+            .replace("ocean_level = 0.5\n", "");
+        assert!(!text.contains("ocean_level"));
+        assert_eq!(
+            toml::from_str::<BedrockConfig>(&text).unwrap().ocean_level,
+            default_ocean_level()
+        );
+    }
+
+    #[test]
+    fn bedrock_within_curve() {
+        let base =
+            Brane::create_by_index(RES, |j| Elevation::confine(j as f64 / RES.square() as f64));
+        for kind in [
+            NoiseKind::OpenSimplex,
+            NoiseKind::Perlin,
+            NoiseKind::Worley,
+            NoiseKind::RidgedMulti,
+            NoiseKind::Billow,
+        ] {
+            let mut config = BedrockConfig::default();
+            config.layers[0].kind = kind;
+            let elevation = bedrock_from(&base, 3, &config);
+            assert!(elevation
+                .grid
+                .iter()
+                .all(|value| (0. ..=1.).contains(&value.release())));
+        }
+    }
+}
@@ -0,0 +1,322 @@
+use crate::{
+    carto::{
+        brane::{Brane, Resolution},
+        datum::DatumZa,
+        honeycomb::HoneyCellToroidal,
+    },
+    climate::circulation::OCNLV,
+    geology::tectonics::scatter,
+    units::{Elevation, Unit},
+};
+use log::trace;
+use noise::{NoiseFn, OpenSimplex, Seedable};
+use ord_subset::OrdVar;
+use std::{
+    collections::{BinaryHeap, VecDeque},
+    f64::consts::TAU,
+};
+
+const SQRT3B2: f64 = 0.8660254;
+
+pub struct Continents {
+    /// fraction of the world above the ocean level
+    pub land_fraction: f64,
+    /// number of separate landmasses, at most a seventh of the data
+    pub count: usize,
+    /// how ragged the coastlines are
+    pub raggedness: f64,
+    /// seeds tried by world generation before settling for the closest count
+    pub attempts: usize,
+    /// level the coasts are raised around
+    pub ocean_level: f64,
+}
+
+impl Default for Continents {
+    fn default() -> Self {
+        Self {
+            land_fraction: 0.32,
+            count: 4,
+            raggedness: 0.48,
+            attempts: 12,
+            ocean_level: OCNLV,
+        }
+    }
+}
+
+/// base elevation with continents rising just above the ocean level
+///     every continent grows from its centre over the highest scores first
+///     and never touches another one, so the land falls into exactly as many
+///     landmasses as there are continents
+pub fn continents(resolution: Resolution, seed: u32, params: &Continents) -> Brane<Elevation> {
+    trace!("raising continents");
+    let one: i32 = resolution.into();
+    // radius of a hexagonal ball covering an even share of the land
+    let radius = (params.land_fraction * resolution.square() as f64
+        / (3. * params.count.max(1) as f64))
+        .sqrt()
+        .max(1.);
+
+    // keep centres apart, loosening the distance whenever they do not fit
+    assert!(
+        7 * params.count <= resolution.square(),
+        "every continent needs room for its centre and a moat around it"
+    );
+    let fits = |candidate: &DatumZa, centres: &[DatumZa], separation: f64| {
+        centres
+            .iter()
+            .all(|centre| candidate.dist_toroidal(centre, one) as f64 >= separation)
+    };
+    let mut centres = Vec::<DatumZa>::with_capacity(params.count);
+    let mut separation = 2. * radius;
+    let mut index = 0;
+    while centres.len() < params.count && index < 64 * params.count as u64 {
+        let candidate = DatumZa::new(
+            (scatter(seed, 2 * index) * one as f64) as i32,
+            (scatter(seed, 2 * index + 1) * one as f64) as i32,
+        );
+        if fits(&candidate, &centres, separation) {
+            centres.push(candidate);
+        }
+        index += 1;
+        if index % 64 == 0 {
+            separation = (separation * 0.8).max(2.);
+        }
+    }
+    // whatever did not fit by chance is placed in order
+    //     every datum ends up next to a centre, so a seventh of the torus always fits
+    for jndex in 0..resolution.square() {
+        if centres.len() >= params.count {
+            break;
+        }
+        let candidate = DatumZa::enravel(jndex, resolution);
+        if fits(&candidate, &centres, 2.) {
+            centres.push(candidate);
+        }
+    }
+
+    // prepare noise
+    let noise = OpenSimplex::new().set_seed(seed);
+    let toroidal_sample = |jndex: usize| -> f64 {
+        let datum = DatumZa::enravel(jndex, resolution).cast(resolution);
+        let x: f64 = TAU * datum.x;
+        let y: f64 = TAU * (datum.x + datum.y); // undistort geometry on the hexagon
+        [(2., 2f64.recip()), (4., 4f64.recip()), (8., 8f64.recip())]
+            .iter()
+            .map(|(frequency, amplitude)| {
+                amplitude
+                    * noise.get([
+                        // toroidal wrapping
+                        frequency * x.cos(),
+                        frequency * x.sin(),
+                        frequency * SQRT3B2 * y.cos(), // undistort geometry on the hexagon
+                        frequency * SQRT3B2 * y.sin(), // undistort geometry on the hexagon
+                    ])
+            })
+            .sum::<f64>()
+    };
+
+    // closeness to the nearest continent centre, roughened by noise
+    let score = Brane::<f64>::create_by_index(resolution, |jndex| {
+        let datum = DatumZa::enravel(jndex, resolution);
+        centres
+            .iter()
+            .map(|centre| 1. - datum.dist_toroidal(centre, one) as f64 / radius)
+            .fold(f64::NEG_INFINITY, f64::max)
+            + params.raggedness * toroidal_sample(jndex)
+    });
+
+    // grow all continents at once, always claiming the best datum on any coast
+    //     the centres are claimed first, so no continent can swallow another's
+    let land = (params.land_fraction.clamp(0., 1.) * resolution.square() as f64).round() as usize;
+    let mut claim = vec![None::<usize>; resolution.square()];
+    let mut queue = BinaryHeap::new();
+    let mut claimed = 0;
+    for (continent, centre) in centres.iter().enumerate() {
+        claim[centre.unravel(resolution)] = Some(continent);
+        claimed += 1;
+    }
+    for (continent, centre) in centres.iter().enumerate() {
+        for neighbour in centre.ambit_toroidal(one) {
+            let jndex = neighbour.unravel(resolution);
+            queue.push((OrdVar::new(score.grid[jndex]), jndex, continent));
+        }
+    }
+    while let Some((_, jndex, continent)) = queue.pop() {
+        if claimed >= land {
+            break;
+        }
+        let here = DatumZa::enravel(jndex, resolution);
+        let touches_other = here.ambit_toroidal(one).iter().any(|neighbour| {
+            matches!(claim[neighbour.unravel(resolution)], Some(other) if other != continent)
+        });
+        if claim[jndex].is_some() || touches_other {
+            continue;
+        }
+        claim[jndex] = Some(continent);
+        claimed += 1;
+        for neighbour in here.ambit_toroidal(one) {
+            let jndex = neighbour.unravel(resolution);
+            if claim[jndex].is_none() {
+                queue.push((OrdVar::new(score.grid[jndex]), jndex, continent));
+            }
+        }
+    }
+
+    // the coast lies at the lowest score claimed, and straits between continents stay wet
+    let coast = (0..resolution.square())
+        .filter(|jndex| claim[*jndex].is_some())
+        .map(|jndex| score.grid[jndex])
+        .fold(f64::INFINITY, f64::min);
+    score.operate_by_index(|jndex| {
+        let height = params.ocean_level + (score.grid[jndex] - coast) * 2f64.recip();
+        Elevation::confine(
+            match claim[jndex] {
+                Some(_) => height,
+                None => height.min(params.ocean_level - 256f64.recip()),
+            }
+            .clamp(0., 1.),
+        )
+    })
+}
+
+/// sizes of the connected landmasses above a given ocean level
+pub fn landmasses(elevation: &Brane<Elevation>, ocean: Elevation) -> Vec<usize> {
+    let resolution = elevation.resolution;
+    let one: i32 = resolution.into();
+    let mut visited = vec![false; resolution.square()];
+    let mut sizes = Vec::new();
+    for start in 0..resolution.square() {
+        if visited[start] || elevation.grid[start] < ocean {
+            continue;
+        }
+        visited[start] = true;
+        let mut size = 0;
+        let mut queue = VecDeque::from([DatumZa::enravel(start, resolution)]);
+        while let Some(here) = queue.pop_front() {
+            size += 1;
+            for neighbour in here.ambit_toroidal(one) {
+                let jndex = neighbour.unravel(resolution);
+                if !visited[jndex] && elevation.grid[jndex] >= ocean {
+                    visited[jndex] = true;
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+        sizes.push(size);
+    }
+    sizes
+}
+
+/// number of landmasses holding at least an eighth of an even share of the land
+///     smaller islets do not count as continents
+pub fn continent_count(
+    elevation: &Brane<Elevation>,
+    ocean: Elevation,
+    params: &Continents,
+) -> usize {
+    let sizes = landmasses(elevation, ocean);
+    let smallest = sizes.iter().sum::<usize>() as f64 / (8. * params.count.max(1) as f64);
+    sizes
+        .into_iter()
+        .filter(|size| *size as f64 >= smallest)
+        .count()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    const RES: Resolution = Resolution::confine(24);
+
+    #[test]
+    fn continents_land_fraction() {
+        let params = Continents {
+            ocean_level: 0.5,
+            ..Continents::default()
+        };
+        let elevation = continents(RES, 5, &params);
+        let land = elevation
+            .grid
+            .iter()
+            .filter(|value| value.release() >= params.ocean_level)
+            .count() as f64
+            / RES.square() as f64;
+        assert!((land - params.land_fraction).abs() <= 2. / RES.release() as f64);
+    }
+
+    #[test]
+    fn continents_land_fraction_extremes() {
+        for land_fraction in [0., 1.] {
+            let params = Continents {
+                land_fraction,
+                count: 1,
+                ..Continents::default()
+            };
+            let elevation = continents(RES, 5, &params);
+            let land = elevation
+                .grid
+                .iter()
+                .filter(|value| value.release() >= OCNLV)
+                .count() as f64
+                / RES.square() as f64;
+            assert!((land - land_fraction).abs() <= 2. / RES.release() as f64);
+        }
+    }
+
+    #[test]
+    fn continents_never_merge() {
+        for seed in 0..8 {
+            for count in [1, 3, 6] {
+                let params = Continents {
+                    count,
+                    ..Continents::default()
+                };
+                let elevation = continents(RES, seed, &params);
+                let ocean = Elevation::confine(params.ocean_level);
+                assert_eq!(landmasses(&elevation, ocean).len(), count);
+                assert_eq!(continent_count(&elevation, ocean, &params), count);
+            }
+        }
+    }
+
+    #[test]
+    fn continents_crowded() {
+        let params = Continents {
+            count: RES.square() / 7,
+            ..Continents::default()
+        };
+        let elevation = continents(RES, 3, &params);
+        let ocean = Elevation::confine(params.ocean_level);
+        assert_eq!(landmasses(&elevation, ocean).len(), params.count);
+    }
+
+    #[test]
+    #[should_panic(expected = "every continent needs room")]
+    fn continents_without_room() {
+        let params = Continents {
+            count: RES.square() / 7 + 1,
+            ..Continents::default()
+        };
+        continents(RES, 3, &params);
+    }
+
+    #[test]
+    fn landmasses_label_connected_land() {
+        // a ring of land around the torus, one island and a single datum islet
+        let elevation = Brane::create_by_index(RES, |j| {
+            let datum = DatumZa::enravel(j, RES);
+            let land = datum.y == 0
+                || (datum.x >= 4 && datum.x < 8 && datum.y >= 6 && datum.y < 10)
+                || (datum.x == 16 && datum.y == 16);
+            Elevation::confine(if land { 1. } else { 0. })
+        });
+        let ocean = Elevation::confine(0.5);
+        let mut sizes = landmasses(&elevation, ocean);
+        sizes.sort_unstable();
+        assert_eq!(sizes, vec![1, 16, 24]);
+        let params = Continents {
+            count: 2,
+            ..Continents::default()
+        };
+        assert_eq!(continent_count(&elevation, ocean, &params), 2);
+    }
+}
@@ -0,0 +1,258 @@
+use crate::{
+    carto::{
+        brane::{Brane, Resolution},
+        datum::DatumZa,
+        flux::Flux,
+        honeycomb::HoneyCellToroidal,
+    },
+    climate::circulation::shed,
+    units::{Elevation, Precipitation, Unit},
+    vars::HEX_AREA,
+};
+use log::trace;
+use noise::{NoiseFn, OpenSimplex, Seedable};
+use petgraph::{visit::EdgeRef, Direction};
+use std::f64::consts::TAU;
+
+/// distance in meters between the centres of neighbouring hexagons
+pub fn spacing(resolution: Resolution) -> f64 {
+    let area = HEX_AREA * 1_000_000. / resolution.square() as f64;
+    (2. * area / 3f64.sqrt()).sqrt()
+}
+
+/* # hydraulic erosion */
+
+pub struct HydraulicErosion {
+    /// number of iterations of the whole process
+    pub steps: usize,
+    /// seed for the variation of rainfall between steps
+    pub seed: u32,
+    /// relative variation of rainfall between steps
+    pub storminess: f64,
+    /// how easily the bedrock gets incised
+    pub erodibility: f64,
+    /// exponent of discharge in the stream power law
+    pub discharge_exponent: f64,
+    /// exponent of slope in the stream power law
+    pub slope_exponent: f64,
+    /// how much sediment the water can carry
+    pub capacity: f64,
+    /// fraction of excess sediment dropped at every datum
+    pub deposition: f64,
+}
+
+impl Default for HydraulicErosion {
+    fn default() -> Self {
+        Self {
+            steps: 24,
+            seed: 0,
+            storminess: 0.24,
+            erodibility: 0.0002,
+            discharge_exponent: 0.5,
+            slope_exponent: 1.,
+            capacity: 0.0004,
+            deposition: 0.36,
+        }
+    }
+}
+
+/// incise valleys with stream power driven by the discharge of a watershed
+pub fn hydraulic(
+    elevation: &Brane<Elevation>,
+    rainfall: &Brane<Precipitation>,
+    params: &HydraulicErosion,
+) -> Brane<Elevation> {
+    trace!("eroding bedrock with water");
+    let resolution = elevation.resolution;
+    let noise = OpenSimplex::new().set_seed(params.seed);
+    let mut elevation = elevation.clone();
+
+    for step in 0..params.steps {
+        // vary rainfall between steps, wrapping the noise around the torus
+        let rainfall_step = Brane::create_by_index(resolution, |jndex| {
+            let datum = DatumZa::enravel(jndex, resolution).cast(resolution);
+            let x = TAU * datum.x;
+            let y = TAU * (datum.x + datum.y);
+            rainfall.grid[jndex]
+                * (1.
+                    + params.storminess
+                        * noise.get([
+                            3. * x.cos() + step as f64 * 7.,
+                            3. * x.sin(),
+                            3. * y.cos(),
+                            3. * y.sin(),
+                        ]))
+                .max(0.)
+        });
+        let slope = Flux::from(elevation.clone());
+        let discharge = shed(&slope, &rainfall_step);
+        let mut sediment = vec![0f64; resolution.square()];
+
+        // sediment is carried from the sources down to the sinks
+        for node in slope.breadth().into_iter().rev() {
+            let jndex = slope.graph[node].unravel(resolution);
+            let load = sediment[jndex];
+            match slope.graph.edges_directed(node, Direction::Outgoing).next() {
+                Some(edge) => {
+                    let kndex = slope.graph[edge.target()].unravel(resolution);
+                    let drop = edge.weight().release();
+                    let gradient = drop * f64::from(resolution);
+                    let flow = discharge.grid[jndex].release().max(0.);
+                    let capacity = params.capacity * flow * gradient;
+                    let change = if load > capacity {
+                        // drop part of what cannot be carried
+                        -(params.deposition * (load - capacity))
+                    } else {
+                        // incise, never digging below the target
+                        (params.erodibility
+                            * flow.powf(params.discharge_exponent)
+                            * gradient.powf(params.slope_exponent))
+                        .min(capacity - load)
+                        .min(drop * 2f64.recip())
+                    };
+                    elevation.grid[jndex] = elevation.grid[jndex] - Elevation::confine(change);
+                    sediment[kndex] += load + change;
+                }
+                None => {
+                    // sinks keep everything that reaches them
+                    elevation.grid[jndex] = elevation.grid[jndex] + Elevation::confine(load);
+                }
+            }
+        }
+    }
+
+    elevation
+}
+
+/* # thermal erosion */
+
+pub struct ThermalErosion {
+    /// largest number of iterations, if the slopes do not settle earlier
+    pub steps: usize,
+    /// steepest stable slope between neighbouring data, in radians
+    pub talus: f64,
+    /// fraction of the excess material moved at every step
+    pub rate: f64,
+}
+
+impl Default for ThermalErosion {
+    fn default() -> Self {
+        Self {
+            steps: 48,
+            talus: 0.024,
+            rate: 0.5,
+        }
+    }
+}
+
+/// let material slide down slopes steeper than the talus angle
+pub fn thermal(elevation: &Brane<Elevation>, params: &ThermalErosion) -> Brane<Elevation> {
+    trace!("eroding bedrock with gravity");
+    let resolution = elevation.resolution;
+    let threshold =
+        params.talus.tan() * spacing(resolution) / Elevation::confine(1.).meters() as f64;
+    let mut elevation = elevation.release();
+
+    for _ in 0..params.steps {
+        // material leaving every datum towards each of its neighbours
+        let outflow = elevation.operate_by_index(|jndex| {
+            let here = elevation.grid[jndex];
+            let excess = DatumZa::enravel(jndex, resolution)
+                .ambit_toroidal(resolution.into())
+                .map(|neighbour| {
+                    (here - elevation.grid[neighbour.unravel(resolution)] - threshold).max(0.)
+                });
+            let total = excess.iter().sum::<f64>();
+            let moved = params.rate * excess.iter().copied().fold(0., f64::max) * 2f64.recip();
+            excess.map(|e| if total > 0. { moved * e / total } else { 0. })
+        });
+        if outflow.grid.iter().all(|out| out.iter().all(|o| *o == 0.)) {
+            break;
+        }
+
+        elevation = elevation.operate_by_index(|jndex| {
+            let inflow = DatumZa::enravel(jndex, resolution)
+                .ambit_toroidal(resolution.into())
+                .iter()
+                .enumerate()
+                .map(|(direction, neighbour)| {
+                    // the neighbour sends material here in the opposite direction
+                    outflow.grid[neighbour.unravel(resolution)][(direction + 3) % 6]
+                })
+                .sum::<f64>();
+            elevation.grid[jndex] - outflow.grid[jndex].iter().sum::<f64>() + inflow
+        });
+    }
+
+    elevation.operate_by_value(Elevation::confine)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use float_eq::assert_float_eq;
+    const EPSILON: f64 = 0.0000_01;
+    const RES: Resolution = Resolution::confine(12);
+
+    fn hill() -> Brane<Elevation> {
+        Brane::create_by_datum(RES, |datum| {
+            Elevation::confine(
+                ((TAU * datum.x).sin() + (TAU * (datum.x + datum.y)).cos()) * 0.24 + 0.5,
+            )
+        })
+    }
+
+    #[test]
+    fn hydraulic_conserves_mass() {
+        let elevation = hill();
+        let rainfall = Brane::create_by_index(RES, |_| Precipitation::confine(0.12));
+        let eroded = hydraulic(&elevation, &rainfall, &HydraulicErosion::default());
+        assert_float_eq!(
+            elevation.release().grid.iter().sum::<f64>(),
+            eroded.release().grid.iter().sum::<f64>(),
+            abs <= EPSILON
+        );
+    }
+
+    #[test]
+    fn hydraulic_carves_towards_the_sink() {
+        // a cone draining into the origin, which is the only sink
+        let origin = DatumZa::new(0, 0);
+        let elevation = Brane::create_by_index(RES, |j| {
+            Elevation::confine(
+                0.5 + 0.04 * DatumZa::enravel(j, RES).dist_toroidal(&origin, RES.into()) as f64,
+            )
+        });
+        let rainfall = Brane::create_by_index(RES, |_| Precipitation::confine(0.12));
+        let params = HydraulicErosion {
+            steps: 4,
+            storminess: 0.,
+            ..HydraulicErosion::default()
+        };
+        let eroded = hydraulic(&elevation, &rainfall, &params);
+
+        // the slopes are worn down and the sediment settles in the sink
+        let sink = origin.unravel(RES);
+        assert!(eroded.grid[sink] > elevation.grid[sink]);
+        for j in (0..RES.square()).filter(|j| *j != sink) {
+            assert!(eroded.grid[j] <= elevation.grid[j]);
+        }
+        for neighbour in origin.ambit_toroidal(RES.into()) {
+            let j = neighbour.unravel(RES);
+            assert!(eroded.grid[j] < elevation.grid[j]);
+        }
+    }
+
+    #[test]
+    fn thermal_conserves_mass() {
+        let mut elevation = hill();
+        elevation.grid[0] = Elevation::confine(24.);
+        let relaxed = thermal(&elevation, &ThermalErosion::default());
+        assert_float_eq!(
+            elevation.release().grid.iter().sum::<f64>(),
+            relaxed.release().grid.iter().sum::<f64>(),
+            abs <= EPSILON
+        );
+        assert!(relaxed.grid[0] < elevation.grid[0]);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{
+    carto::{brane::Brane, datum::DatumZa, honeycomb::HoneyCellToroidal},
+    units::{Elevation, Unit},
+};
+use log::trace;
+
+pub struct Isostasy {
+    /// flexural radius of the lithosphere, as a fraction of the torus
+    pub radius: f64,
+    /// fraction of the regional load compensated by subsidence
+    pub compensation: f64,
+}
+
+impl Default for Isostasy {
+    fn default() -> Self {
+        Self {
+            radius: 0.024,
+            compensation: 0.36,
+        }
+    }
+}
+
+/// sink regional loads into the mantle and lift regional lows
+///     the load is spread by a hexagonal cone kernel, which keeps the mean elevation
+pub fn isostasy(elevation: &Brane<Elevation>, params: &Isostasy) -> Brane<Elevation> {
+    trace!("compensating loads isostatically");
+    let resolution = elevation.resolution;
+    let one: i32 = resolution.into();
+    let radius = (params.radius * one as f64).round().max(1.) as i32;
+    let elevation = elevation.release();
+    let mean = elevation.grid.iter().sum::<f64>() / resolution.square() as f64;
+
+    // weights of the kernel sum up to one
+    let weights = (0..=radius)
+        .map(|distance| (radius + 1 - distance) as f64)
+        .collect::<Vec<f64>>();
+    let total = (0..=radius)
+        .map(|distance| weights[distance as usize] * (6 * distance).max(1) as f64)
+        .sum::<f64>();
+
+    elevation.operate_by_index(|jndex| {
+        let here = DatumZa::enravel(jndex, resolution);
+        let regional = (0..=radius)
+            .map(|distance| {
+                let ring = if distance == 0 {
+                    vec![here]
+                } else {
+                    here.ring_toroidal(distance, one)
+                };
+                weights[distance as usize]
+                    * ring
+                        .iter()
+                        .map(|datum| elevation.grid[datum.unravel(resolution)])
+                        .sum::<f64>()
+            })
+            .sum::<f64>()
+            / total;
+        Elevation::confine(
+            (elevation.grid[jndex] - params.compensation * (regional - mean)).clamp(0., 1.),
+        )
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::carto::brane::Resolution;
+    use float_eq::assert_float_eq;
+    const RES: Resolution = Resolution::confine(24);
+
+    #[test]
+    fn isostasy_keeps_mean() {
+        let elevation = Brane::create_by_index(RES, |j| {
+            Elevation::confine(0.25 + 0.5 * j as f64 / RES.square() as f64)
+        });
+        let compensated = isostasy(&elevation, &Isostasy::default());
+        let mean = |brane: &Brane<Elevation>| {
+            brane.grid.iter().map(|e| e.release()).sum::<f64>() / RES.square() as f64
+        };
+        assert_float_eq!(mean(&elevation), mean(&compensated), abs <= 0.000001);
+    }
+
+    #[test]
+    fn isostasy_sinks_loads() {
+        let mut elevation = Brane::create_by_index(RES, |_| Elevation::confine(0.25));
+        let peak = DatumZa::new(12, 12);
+        elevation.grid[peak.unravel(RES)] = Elevation::confine(0.75);
+        let compensated = isostasy(&elevation, &Isostasy::default());
+        assert!(compensated.grid[peak.unravel(RES)].release() < 0.75);
+        // the load bends the crust around it into a moat
+        for datum in peak.ambit_toroidal(RES.into()) {
+            assert!(compensated.grid[datum.unravel(RES)].release() < 0.25);
+        }
+        // far away the crust rises a little
+        assert!(compensated.grid[DatumZa::new(0, 0).unravel(RES)].release() > 0.25);
+    }
+}
@@ -0,0 +1,68 @@
+use crate::{
+    carto::{brane::Brane, datum::DatumZa, honeycomb::HoneyCellToroidal},
+    units::{Elevation, Precipitation, Unit},
+};
+use log::trace;
+
+#[derive(Clone, Debug)]
+pub struct Soils {
+    /// water held by the deepest soils, on flat lowland
+    pub capacity: Precipitation,
+    /// local relief in meters halving the soil depth
+    pub relief: f64,
+    /// altitude above the ocean in meters halving the soil depth
+    pub highland: f64,
+}
+
+impl Default for Soils {
+    fn default() -> Self {
+        Self {
+            capacity: Precipitation::confine(0.48),
+            relief: 324.,
+            highland: 2592.,
+        }
+    }
+}
+
+/// water holding capacity of the soil
+///     soils are washed off steep slopes and thin out on high ground
+pub fn soil_capacity(
+    elevation: &Brane<Elevation>,
+    ocean_level: Elevation,
+    params: &Soils,
+) -> Brane<Precipitation> {
+    trace!("laying down soils");
+    let resolution = elevation.resolution;
+    elevation.operate_by_index(|jndex| {
+        let here = elevation.grid[jndex];
+        let relief = DatumZa::enravel(jndex, resolution)
+            .ambit_toroidal(resolution.into())
+            .into_iter()
+            .map(|datum| (here.meters() - elevation.grid[datum.unravel(resolution)].meters()).abs())
+            .max()
+            .unwrap_or(0) as f64;
+        let altitude = (here.meters() - ocean_level.meters()).max(0) as f64;
+        params.capacity / (1. + relief / params.relief) / (1. + altitude / params.highland)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::carto::brane::Resolution;
+    const RES: Resolution = Resolution::confine(12);
+
+    #[test]
+    fn soils_thin_out_on_slopes() {
+        let flat = Brane::create_by_index(RES, |_| Elevation::confine(0.5));
+        let ridged = Brane::create_by_index(RES, |j| {
+            Elevation::confine(if j % 2 == 0 { 0.5 } else { 0.52 })
+        });
+        let ocean_level = Elevation::confine(0.5);
+        let on_flat = soil_capacity(&flat, ocean_level, &Soils::default());
+        let on_ridges = soil_capacity(&ridged, ocean_level, &Soils::default());
+        assert_eq!(on_flat.grid[0], Soils::default().capacity);
+        assert!(on_ridges.grid[0] < on_flat.grid[0]);
+        assert!(on_ridges.grid[1] < on_ridges.grid[0]);
+    }
+}
@@ -0,0 +1,336 @@
+use crate::{
+    carto::{
+        brane::{Brane, Resolution},
+        datum::{DatumRe, DatumZa},
+        honeycomb::{Direction, Hexagon, HoneyCellPlanar, HoneyCellToroidal},
+    },
+    climate::circulation::OCNLV,
+    units::{Elevation, Unit},
+};
+use log::trace;
+use std::{collections::VecDeque, f64::consts::TAU};
+
+/// deterministic pseudo random number from the unit interval
+pub fn scatter(seed: u32, index: u64) -> f64 {
+    // splitmix64
+    let mut z = ((seed as u64) << 32) ^ index.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/* # plates */
+
+pub struct Tectonics {
+    pub seed: u32,
+    /// number of plates, at least one
+    ///     a single plate covers the whole torus and has no margins
+    pub plates: usize,
+    /// fraction of plates carrying continental crust
+    pub continental: f64,
+    /// level continental crust rises above and oceanic crust sinks below
+    pub ocean_level: f64,
+}
+
+impl Default for Tectonics {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            plates: 12,
+            continental: 0.36,
+            ocean_level: OCNLV,
+        }
+    }
+}
+
+pub struct Plate {
+    /// datum from which the plate was grown
+    pub centre: DatumZa,
+    /// drift of the plate, in hexagon centre coordinates
+    pub motion: DatumRe,
+    pub continental: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Boundary {
+    Convergent,
+    Divergent,
+    Transform,
+}
+
+/// the edge of a plate, where it touches another one
+#[derive(Clone, Copy, Debug)]
+pub struct Margin {
+    pub boundary: Boundary,
+    /// index of the plate on the other side
+    pub neighbour: usize,
+    /// magnitude of the relative motion of the two plates
+    pub stress: f64,
+}
+
+pub struct Lithosphere {
+    pub plates: Vec<Plate>,
+    /// index of the plate every datum belongs to
+    pub partition: Brane<usize>,
+    /// margins at data touching another plate
+    pub margins: Brane<Option<Margin>>,
+}
+
+impl Lithosphere {
+    /// seed plates and grow them into a voronoi partition of the torus
+    pub fn new(resolution: Resolution, params: &Tectonics) -> Self {
+        assert!(
+            params.plates > 0,
+            "the lithosphere needs at least one plate"
+        );
+        trace!("drifting tectonic plates");
+        let one: i32 = resolution.into();
+        let continents = (params.continental * params.plates as f64).round() as usize;
+        let plates = (0..params.plates)
+            .map(|p| {
+                let index = 4 * p as u64;
+                let angle = TAU * scatter(params.seed, index + 2);
+                let speed = 2f64.recip() + 2f64.recip() * scatter(params.seed, index + 3);
+                Plate {
+                    centre: DatumZa::new(
+                        (scatter(params.seed, index) * one as f64) as i32,
+                        (scatter(params.seed, index + 1) * one as f64) as i32,
+                    ),
+                    motion: DatumRe::new(angle.cos(), angle.sin()) * speed,
+                    continental: p < continents,
+                }
+            })
+            .collect::<Vec<Plate>>();
+
+        // grow plates outwards from their centres
+        let mut partition = Brane::new(vec![usize::MAX; resolution.square()], resolution);
+        let mut queue = VecDeque::new();
+        for (index, plate) in plates.iter().enumerate() {
+            let jndex = plate.centre.unravel(resolution);
+            if partition.grid[jndex] == usize::MAX {
+                partition.grid[jndex] = index;
+                queue.push_back(plate.centre);
+            }
+        }
+        while let Some(here) = queue.pop_front() {
+            let plate = partition.grid[here.unravel(resolution)];
+            for neighbour in here.ambit_toroidal(one) {
+                let jndex = neighbour.unravel(resolution);
+                if partition.grid[jndex] == usize::MAX {
+                    partition.grid[jndex] = plate;
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+
+        // classify boundaries by the relative motion of plates
+        let margins = partition.operate_by_index(|jndex| {
+            let here = DatumZa::enravel(jndex, resolution);
+            let plate = partition.grid[jndex];
+            let (mut closing, mut shear, mut neighbour) = (0f64, 0f64, None);
+            for direction in Direction::array() {
+                let other =
+                    partition.grid[here.neighbour_toroidal(direction, one).unravel(resolution)];
+                if other != plate {
+                    let normal = here.neighbour_planar(direction).centre() - here.centre();
+                    let normal = normal / (normal.x.powi(2) + normal.y.powi(2)).sqrt();
+                    let relative = plates[plate].motion - plates[other].motion;
+                    closing += relative.x * normal.x + relative.y * normal.y;
+                    shear += (relative.x * normal.y - relative.y * normal.x).abs();
+                    neighbour = Some(other);
+                }
+            }
+            neighbour.map(|neighbour| Margin {
+                boundary: if closing.abs() < shear * 2f64.recip() {
+                    Boundary::Transform
+                } else if closing > 0. {
+                    Boundary::Convergent
+                } else {
+                    Boundary::Divergent
+                },
+                neighbour,
+                stress: closing.abs().max(shear),
+            })
+        });
+
+        Self {
+            plates,
+            partition,
+            margins,
+        }
+    }
+
+    /// raise mountains, rifts, trenches and shelves along the margins
+    pub fn elevation(&self, ocean_level: f64) -> Brane<Elevation> {
+        trace!("building relief from plate margins");
+        let resolution = self.partition.resolution;
+        let width = f64::from(resolution) * 48f64.recip(); // typical width of features in data
+
+        // nearest margin of the same plate, and the distance to it
+        let mut nearest = Brane::new(
+            vec![None::<(usize, usize)>; resolution.square()],
+            resolution,
+        );
+        let mut queue = VecDeque::new();
+        for jndex in 0..resolution.square() {
+            if self.margins.grid[jndex].is_some() {
+                nearest.grid[jndex] = Some((0, jndex));
+                queue.push_back(DatumZa::enravel(jndex, resolution));
+            }
+        }
+        while let Some(here) = queue.pop_front() {
+            let (distance, source) =
+                nearest.grid[here.unravel(resolution)].expect("queued data have a margin");
+            for neighbour in here.ambit_toroidal(resolution.into()) {
+                let jndex = neighbour.unravel(resolution);
+                if nearest.grid[jndex].is_none()
+                    && self.partition.grid[jndex] == self.partition.grid[source]
+                {
+                    nearest.grid[jndex] = Some((distance + 1, source));
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+
+        self.partition.operate_by_index(|jndex| {
+            let plate = &self.plates[self.partition.grid[jndex]];
+            let base = if plate.continental {
+                ocean_level + 0.06
+            } else {
+                ocean_level - 0.18
+            };
+            let relief = match nearest.grid[jndex] {
+                Some((distance, source)) => {
+                    let margin = self.margins.grid[source].expect("source is a margin");
+                    let other = &self.plates[margin.neighbour];
+                    let d = distance as f64 / width;
+                    let bell = |centre: f64, spread: f64| (-((d - centre) / spread).powi(2)).exp();
+                    let feature = match (margin.boundary, plate.continental, other.continental) {
+                        // mountain ranges
+                        (Boundary::Convergent, true, _) => {
+                            0.36 * margin.stress.min(1.) * bell(0., 1.)
+                        }
+                        // trenches where oceanic crust subducts
+                        (Boundary::Convergent, false, true) => -0.12 * bell(0., 0.5),
+                        // island arcs behind a trench
+                        (Boundary::Convergent, false, false) => {
+                            0.24 * margin.stress.min(1.) * bell(1., 0.5) - 0.08 * bell(0., 0.5)
+                        }
+                        // rift valleys
+                        (Boundary::Divergent, true, _) => -0.08 * bell(0., 0.5),
+                        // mid ocean ridges
+                        (Boundary::Divergent, false, _) => 0.06 * bell(0., 1.),
+                        (Boundary::Transform, _, _) => 0.02 * bell(0., 1.),
+                    };
+                    // passive margins get a continental shelf and a gentle coast
+                    let shelf = match (margin.boundary, plate.continental, other.continental) {
+                        (Boundary::Convergent, _, _) => 0.,
+                        (_, false, true) => 0.16 * (-d).exp(),
+                        (_, true, false) => -0.04 * (-d).exp(),
+                        _ => 0.,
+                    };
+                    feature + shelf
+                }
+                None => 0.,
+            };
+            Elevation::confine((base + relief).clamp(0., 1.))
+        })
+    }
+}
+
+/// procedural elevation built from drifting plates
+pub fn tectonics(resolution: Resolution, params: &Tectonics) -> Brane<Elevation> {
+    Lithosphere::new(resolution, params).elevation(params.ocean_level)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    const RES: Resolution = Resolution::confine(24);
+
+    #[test]
+    fn scatter_unit_interval() {
+        for index in 0..64 {
+            let value = scatter(3, index);
+            assert!((0. ..1.).contains(&value));
+        }
+        assert_eq!(scatter(3, 7), scatter(3, 7));
+        assert_ne!(scatter(3, 7), scatter(4, 7));
+    }
+
+    #[test]
+    fn lithosphere_partition() {
+        let lithosphere = Lithosphere::new(RES, &Tectonics::default());
+        assert!(lithosphere
+            .partition
+            .grid
+            .iter()
+            .all(|plate| *plate < lithosphere.plates.len()));
+        for jndex in 0..RES.square() {
+            if let Some(margin) = lithosphere.margins.grid[jndex] {
+                assert_ne!(margin.neighbour, lithosphere.partition.grid[jndex]);
+            }
+        }
+        assert!(lithosphere
+            .margins
+            .grid
+            .iter()
+            .any(|margin| margin.is_some()));
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one plate")]
+    fn lithosphere_without_plates() {
+        Lithosphere::new(
+            RES,
+            &Tectonics {
+                plates: 0,
+                ..Tectonics::default()
+            },
+        );
+    }
+
+    #[test]
+    fn lithosphere_single_plate() {
+        let lithosphere = Lithosphere::new(
+            RES,
+            &Tectonics {
+                plates: 1,
+                ..Tectonics::default()
+            },
+        );
+        assert!(lithosphere.partition.grid.iter().all(|plate| *plate == 0));
+        assert!(lithosphere
+            .margins
+            .grid
+            .iter()
+            .all(|margin| margin.is_none()));
+    }
+
+    #[test]
+    fn tectonics_elevation() {
+        let params = Tectonics {
+            seed: 42,
+            ocean_level: 0.5,
+            ..Tectonics::default()
+        };
+        let elevation = tectonics(RES, &params);
+        assert!(elevation
+            .grid
+            .iter()
+            .all(|value| (0. ..=1.).contains(&value.release())));
+        assert!(elevation
+            .grid
+            .iter()
+            .any(|value| value.release() > params.ocean_level));
+        assert!(elevation
+            .grid
+            .iter()
+            .any(|value| value.release() < params.ocean_level));
+        assert_eq!(
+            elevation.release().grid,
+            tectonics(RES, &params).release().grid
+        );
+    }
+}
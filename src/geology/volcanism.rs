@@ -0,0 +1,123 @@
+use crate::{
+    carto::{
+        brane::{Brane, Resolution},
+        datum::DatumRe,
+        honeycomb::HoneyCellToroidal,
+    },
+    geology::tectonics::scatter,
+    units::{Elevation, Unit},
+};
+use log::trace;
+
+pub struct Hotspots {
+    pub seed: u32,
+    /// number of mantle plumes
+    pub count: usize,
+    /// number of volcanoes in every chain, including the active one
+    pub chain: usize,
+    /// offset of every older volcano from the younger one, as a fraction of the torus
+    pub drift: DatumRe,
+    /// height of an active volcano above its base
+    pub height: f64,
+    /// fraction of height kept by every older volcano, as it erodes and subsides
+    pub decay: f64,
+    /// radius of a volcano base, as a fraction of the torus
+    pub radius: f64,
+}
+
+impl Default for Hotspots {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            count: 6,
+            chain: 8,
+            drift: DatumRe::new(0.024, -0.012),
+            height: 0.24,
+            decay: 0.72,
+            radius: 0.012,
+        }
+    }
+}
+
+pub struct Volcanism {
+    pub elevation: Brane<Elevation>,
+    /// data raised by a volcano
+    pub volcanic: Brane<bool>,
+}
+
+/// chains of volcanoes left by plumes as the crust drifts over them
+///     young volcanoes rise as islands, old ones sink into seamounts
+pub fn hotspots(elevation: &Brane<Elevation>, params: &Hotspots) -> Volcanism {
+    trace!("raising volcanic hotspot chains");
+    let resolution = elevation.resolution;
+    let one: i32 = resolution.into();
+    let radius = (params.radius * one as f64).round().max(1.) as i32;
+
+    let mut uplift = vec![0f64; resolution.square()];
+    for plume in 0..params.count as u64 {
+        let origin = DatumRe::new(
+            scatter(params.seed, 2 * plume),
+            scatter(params.seed, 2 * plume + 1),
+        );
+        for age in 0..params.chain {
+            let position = origin + params.drift * age as f64;
+            let vent =
+                DatumRe::new(position.x.rem_euclid(1.), position.y.rem_euclid(1.)).find(resolution);
+            // older volcanoes are eroded and subsided, and no two are quite alike
+            let height = params.height
+                * params.decay.powi(age as i32)
+                * (2f64.recip()
+                    + 2f64.recip()
+                        * scatter(
+                            params.seed,
+                            2 * params.count as u64 + plume * params.chain as u64 + age as u64,
+                        ));
+            uplift[vent.unravel(resolution)] += height;
+            for distance in 1..=radius {
+                for datum in vent.ring_toroidal(distance, one) {
+                    uplift[datum.unravel(resolution)] +=
+                        height * (1. - distance as f64 / (radius + 1) as f64);
+                }
+            }
+        }
+    }
+
+    let uplift = Brane::new(uplift, resolution);
+    Volcanism {
+        elevation: elevation.operate_by_index(|jndex| {
+            Elevation::confine((elevation.grid[jndex].release() + uplift.grid[jndex]).clamp(0., 1.))
+        }),
+        volcanic: uplift.operate_by_value_ref(|value| *value > 0.),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::climate::circulation::OCNLV;
+    const RES: Resolution = Resolution::confine(48);
+
+    #[test]
+    fn hotspots_raise_volcanic_data() {
+        let elevation = Brane::create_by_index(RES, |_| Elevation::confine(OCNLV - 0.12));
+        let params = Hotspots::default();
+        let volcanism = hotspots(&elevation, &params);
+        assert!(volcanism.volcanic.grid.iter().any(|v| *v));
+        for jndex in 0..RES.square() {
+            let before = elevation.grid[jndex].release();
+            let after = volcanism.elevation.grid[jndex].release();
+            assert_eq!(volcanism.volcanic.grid[jndex], after > before);
+            assert!((0. ..=1.).contains(&after));
+        }
+        // some volcanoes break the surface and some stay below
+        assert!(volcanism.elevation.grid.iter().any(|e| e.release() > OCNLV));
+        assert!(volcanism
+            .elevation
+            .operate_by_index(
+                |j| volcanism.volcanic.grid[j] && volcanism.elevation.grid[j].release() < OCNLV
+            )
+            .grid
+            .iter()
+            .any(|v| *v));
+    }
+}
@@ -3,5 +3,7 @@
 
 pub mod carto;
 pub mod climate;
+pub mod geology;
 pub mod regression;
 pub mod units;
+pub mod vars;
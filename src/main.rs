@@ -1,17 +1,38 @@
 use ilmarien::carto::{colour as clr, render::Renderable};
 use log::info;
 
+/// generate a world from a seed and save it for later runs
+#[allow(dead_code)]
+fn run_genesis(seed: u32) {
+    use ilmarien::carto::brane::Resolution;
+    use ilmarien::climate::cosmos::{Cosmos, Genesis};
+    let cosmos = Cosmos::generate(seed, Resolution::confine(324), &Genesis::default());
+    cosmos.save();
+    info!("generated world from seed {}", seed);
+}
+
 #[allow(dead_code)]
 fn run_once() {
-    use ilmarien::climate::circulation::{ocean_tiles, OCNLV};
+    use ilmarien::climate::circulation::ocean_tiles;
     use ilmarien::climate::cosmos::Cosmos;
-    use ilmarien::units::{Elevation, Unit};
-    let cosmos = Cosmos::load();
+    let cosmos = Cosmos::load().expect("saved world should load");
 
-    ocean_tiles(&cosmos.altitude, Elevation::confine(OCNLV))
+    ocean_tiles(&cosmos.altitude, cosmos.ocean_level)
         .operate_by_value(|value| if value { u8::MIN } else { u8::MAX })
         .save_raw_low("land-tiles".to_string());
     /*
+    use ilmarien::geology::{isostasy::*, volcanism::*};
+    let volcanism = hotspots(&cosmos.altitude, &Hotspots::default());
+    let elevation = isostasy(&volcanism.elevation, &Isostasy::default());
+    elevation.render(
+        "elevation".to_string(),
+        clr::TopographyInk::new(cosmos.ocean_level),
+    );
+    volcanism
+        .volcanic
+        .render("volcanic".to_string(), clr::VolcanicInk);
+    */
+    /*
     cosmos.altitude.downgrade(3).render_triple(
         "elevation".to_string(),
         clr::TopographyInk::new(Elevation::confine(3f64.recip())),
@@ -38,6 +59,15 @@ fn run_once() {
     );
     */
     /*
+    use ilmarien::climate::{circulation::SimulationConfig, glacier::Glaciation, orbit::Orbit};
+    let cosmos = cosmos.glaciate(
+        &Glaciation::default(),
+        &Orbit::default(),
+        &SimulationConfig::default(),
+    );
+    cosmos.ice.render("ice".to_string(), clr::GlacierInk);
+    */
+    /*
     use ilmarien::climate::vegetation::Vege;
     let vege = cosmos.vege().operate_by_value(|value| match value {
         Some(zone) => match zone {
@@ -48,7 +78,23 @@ fn run_once() {
         None => 1.,
     });
     vege.save_f64("vege".to_string());
-    // vege.render_triple("vege".to_string(), clr::KoppenInk);
+    // vege.render_triple("vege".to_string(), clr::VegeInk);
+    */
+    /*
+    use ilmarien::{carto::datum::DatumRe, climate::diagram::save_walter_lieth};
+    save_walter_lieth(&cosmos, DatumRe::new(0.5, 0.5), "diagram".to_string());
+    */
+    /*
+    use ilmarien::climate::chart::Index;
+    cosmos
+        .index(Index::DeMartonne)
+        .save_f64("de-martonne".to_string());
+    */
+    /*
+    use ilmarien::climate::{holdridge::Holdridge, koppen::KoppenGeiger, whittaker::Whittaker};
+    cosmos.render_classes(&KoppenGeiger, "koppen".to_string());
+    cosmos.render_classes(&Holdridge, "holdridge".to_string());
+    cosmos.render_classes(&Whittaker, "whittaker".to_string());
     */
     info!("finished simulation");
 }
@@ -56,6 +102,15 @@ fn run_once() {
 fn main() {
     pretty_env_logger::init_timed();
     info!("initialising ilmarien");
-    run_once();
+    // `--genesis <seed>` generates a new world instead of simulating the saved one
+    let args = std::env::args().collect::<Vec<String>>();
+    match args.iter().position(|arg| arg == "--genesis") {
+        Some(flag) => run_genesis(
+            args.get(flag + 1)
+                .and_then(|seed| seed.parse().ok())
+                .unwrap_or(0),
+        ),
+        None => run_once(),
+    }
     info!("computation completed")
 }
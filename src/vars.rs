@@ -12,7 +12,7 @@ pub const RES_SMALL: usize = 144; // resolution for small scale computation
 
 /* ## geology */
 
-//pub const HEX_AREA: f64 = 260956870.632; // area of the entire world in square kilometers
+pub const HEX_AREA: f64 = 260956870.632; // area of the entire world in square kilometers
 
 /* ## hydrology */
 